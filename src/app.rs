@@ -1,3 +1,5 @@
+use std::path::Path;
+
 use clap::{crate_version, App, AppSettings, Arg};
 
 pub fn build_app() -> App<'static, 'static> {
@@ -28,6 +30,17 @@ pub fn build_app() -> App<'static, 'static> {
                          considered to be hidden if their name starts with a `.` sign (dot).",
                 ),
         )
+        .arg(
+            Arg::with_name("no-hidden")
+                .long("no-hidden")
+                .overrides_with_all(&["hidden", "no-hidden"])
+                .hidden_short_help(true)
+                .long_help(
+                    "Do not search hidden files and directories (default). This is useful to \
+                         override a '--hidden' default coming from 'FD_DEFAULT_OPTS' or a \
+                         config file.",
+                ),
+        )
         .arg(
             Arg::with_name("no-ignore")
                 .long("no-ignore")
@@ -49,6 +62,41 @@ pub fn build_app() -> App<'static, 'static> {
                          ignored by '.gitignore' files.",
                 ),
         )
+        .arg(
+            Arg::with_name("no-global-ignore-vcs")
+                .long("no-global-ignore-vcs")
+                .overrides_with("no-global-ignore-vcs")
+                .hidden_short_help(true)
+                .long_help(
+                    "Show search results from files and directories that would otherwise be \
+                         ignored by git's global gitignore file (as configured via \
+                         'core.excludesFile'), while still respecting local '.gitignore' files \
+                         and '.git/info/exclude'. This is different from \
+                         '--no-global-ignore-file', which disables fd's own global ignore file.",
+                ),
+        )
+        .arg(
+            Arg::with_name("no-ignore-dot")
+                .long("no-ignore-dot")
+                .overrides_with("no-ignore-dot")
+                .hidden_short_help(true)
+                .long_help(
+                    "Show search results from files and directories that would otherwise be \
+                         ignored by '.ignore', '.fdignore', or a filename registered via \
+                         '--ignore-file-name', while still respecting '.gitignore' files.",
+                ),
+        )
+        .arg(
+            Arg::with_name("no-ignore-parent")
+                .long("no-ignore-parent")
+                .overrides_with("no-ignore-parent")
+                .hidden_short_help(true)
+                .long_help(
+                    "Do not respect ignore files (.gitignore, .ignore, ..) in parent \
+                         directories, above the search root. Ignore files inside the search \
+                         root are still respected.",
+                ),
+        )
         .arg(
             Arg::with_name("no-global-ignore-file")
                 .long("no-global-ignore-file")
@@ -70,7 +118,8 @@ pub fn build_app() -> App<'static, 'static> {
             Arg::with_name("case-sensitive")
                 .long("case-sensitive")
                 .short("s")
-                .overrides_with_all(&["ignore-case", "case-sensitive"])
+                .overrides_with("case-sensitive")
+                .conflicts_with("ignore-case")
                 .help("Case-sensitive search (default: smart case)")
                 .long_help(
                     "Perform a case-sensitive search. By default, fd uses case-insensitive \
@@ -82,7 +131,7 @@ pub fn build_app() -> App<'static, 'static> {
             Arg::with_name("ignore-case")
                 .long("ignore-case")
                 .short("i")
-                .overrides_with_all(&["case-sensitive", "ignore-case"])
+                .overrides_with("ignore-case")
                 .help("Case-insensitive search (default: smart case)")
                 .long_help(
                     "Perform a case-insensitive search. By default, fd uses case-insensitive \
@@ -109,6 +158,32 @@ pub fn build_app() -> App<'static, 'static> {
                          override --glob.",
                 ),
         )
+        .arg(
+            Arg::with_name("literal-separator")
+                .long("literal-separator")
+                .overrides_with("literal-separator")
+                .conflicts_with("no-literal-separator")
+                .hidden_short_help(true)
+                .long_help(
+                    "When used together with '--glob', prevents '*' from matching the path \
+                         separator ('/'), so e.g. 'src/*' only matches one level deep. This \
+                         already describes fd's default behavior for glob searches; the flag \
+                         exists for symmetry with '--no-literal-separator' and to let scripts \
+                         state that intent explicitly.",
+                ),
+        )
+        .arg(
+            Arg::with_name("no-literal-separator")
+                .long("no-literal-separator")
+                .overrides_with("no-literal-separator")
+                .hidden_short_help(true)
+                .help("'*' matches the path separator in glob searches")
+                .long_help(
+                    "When used together with '--glob', lets '*' match across the path \
+                         separator ('/'), overriding fd's default of confining it to a single \
+                         path component.",
+                ),
+        )
         .arg(
             Arg::with_name("fixed-strings")
                 .long("fixed-strings")
@@ -132,6 +207,45 @@ pub fn build_app() -> App<'static, 'static> {
                     "Shows the full path starting from the root as opposed to relative paths.",
                 ),
         )
+        .arg(
+            Arg::with_name("strip-cwd-prefix")
+                .long("strip-cwd-prefix")
+                .overrides_with("strip-cwd-prefix")
+                .conflicts_with_all(&["absolute-path", "canonicalize"])
+                .hidden_short_help(true)
+                .long_help(
+                    "By default, results carry no leading './' when every search root is the \
+                         current directory (no path given, or an explicitly given '.' root). This \
+                         flag makes that behavior explicit, which is useful for scripts that rely \
+                         on the leading './' being stripped.",
+                ),
+        )
+        .arg(
+            Arg::with_name("canonicalize")
+                .long("canonicalize")
+                .overrides_with("canonicalize")
+                .hidden_short_help(true)
+                .help("Canonicalize each result, resolving symlinks and '..' components")
+                .long_help(
+                    "Canonicalize the paths of each result, resolving symlinks and '..' \
+                     components. If canonicalization fails for a given path (e.g. because of a \
+                     broken symlink), the absolute path is used instead.",
+                ),
+        )
+        .arg(
+            Arg::with_name("relative-to")
+                .overrides_with("relative-to")
+                .long("relative-to")
+                .takes_value(true)
+                .value_name("path")
+                .conflicts_with_all(&["absolute-path", "strip-cwd-prefix", "canonicalize"])
+                .hidden_short_help(true)
+                .long_help(
+                    "Change the output of fd to show file paths relative to the given path \
+                         instead of the current working directory. Results that aren't below the \
+                         given path are shown as an absolute path instead.",
+                ),
+        )
         .arg(
             Arg::with_name("list-details")
                 .long("list-details")
@@ -145,6 +259,48 @@ pub fn build_app() -> App<'static, 'static> {
                          deterministic sort order.",
                 ),
         )
+        .arg(
+            Arg::with_name("format")
+                .overrides_with("format")
+                .long("format")
+                .takes_value(true)
+                .value_name("fmt")
+                .possible_values(&["standard", "json"])
+                .hide_possible_values(true)
+                .conflicts_with_all(&["exec", "exec-batch", "list-details"])
+                .help("Print results as: standard, json")
+                .long_help(
+                    "Declare the output format for search results:\n  \
+                       'standard':  plain paths, one per line (default)\n  \
+                       'json':      newline-delimited JSON objects with 'path', 'file_type', \
+                       'size' and 'modified' fields\n\
+                     Paths that are not valid UTF-8 are converted lossily; such entries are \
+                     marked with 'path_lossy: true'.",
+                ),
+        )
+        .arg(
+            Arg::with_name("classify")
+                .long("classify")
+                .overrides_with("classify")
+                .hidden_short_help(true)
+                .help("Append indicator (one of */@) to entries")
+                .long_help(
+                    "Append an indicator to each path: '/' for directories, '@' for symbolic \
+                     links and '*' for executable files, similar to 'ls -F'.",
+                ),
+        )
+        .arg(
+            Arg::with_name("trailing-slash")
+                .long("trailing-slash")
+                .overrides_with("trailing-slash")
+                .hidden_short_help(true)
+                .long_help(
+                    "Append a trailing '/' to directory results, without marking symbolic \
+                         links or executables like '--classify' does. Has no effect when \
+                         '--classify' is also given, since its own indicator for directories \
+                         is already '/'.",
+                ),
+        )
         .arg(
             Arg::with_name("follow")
                 .long("follow")
@@ -157,6 +313,44 @@ pub fn build_app() -> App<'static, 'static> {
                          flag, symbolic links are also traversed.",
                 ),
         )
+        .arg(
+            Arg::with_name("follow-roots")
+                .long("follow-roots")
+                .overrides_with("follow-roots")
+                .conflicts_with("follow")
+                .hidden_short_help(true)
+                .long_help(
+                    "Descend into search roots that are themselves symlinks, without following \
+                         symbolic links encountered elsewhere during the search. This already \
+                         describes fd's default behavior; the flag exists for symmetry with \
+                         '--follow' and to let scripts state that intent explicitly.",
+                ),
+        )
+        .arg(
+            Arg::with_name("no-follow-roots")
+                .long("no-follow-roots")
+                .overrides_with("no-follow-roots")
+                .conflicts_with_all(&["follow", "follow-roots"])
+                .hidden_short_help(true)
+                .help("Don't descend into search roots that are themselves symlinks")
+                .long_help(
+                    "Don't descend into a search root that is itself a symlink, overriding \
+                         fd's default of following it. Like '--follow-roots', only affects \
+                         search roots; symbolic links encountered elsewhere during the search \
+                         are never followed unless '--follow' is given.",
+                ),
+        )
+        .arg(
+            Arg::with_name("resolve-root-symlink")
+                .long("resolve-root-symlink")
+                .hidden_short_help(true)
+                .long_help(
+                    "When a search root is itself a symlink, display results under it using the \
+                         symlink's resolved (canonicalized) target instead of the symlink path \
+                         that was given on the command line. Has no effect on search roots that \
+                         aren't symlinks, or on broken symlink roots.",
+                ),
+        )
         .arg(
             Arg::with_name("full-path")
                 .long("full-path")
@@ -169,6 +363,30 @@ pub fn build_app() -> App<'static, 'static> {
                          full path.",
                 ),
         )
+        .arg(
+            Arg::with_name("full-path-or-name")
+                .long("full-path-or-name")
+                .conflicts_with("full-path")
+                .hidden_short_help(true)
+                .long_help(
+                    "Match the search pattern against the filename (or directory name), like \
+                         the default behavior, but also consider it a match if it matches \
+                         somewhere in the full path. Unlike '--full-path', this does not require \
+                         the whole path to satisfy the pattern.",
+                ),
+        )
+        .arg(
+            Arg::with_name("follow-and-match-target")
+                .long("follow-and-match-target")
+                .conflicts_with_all(&["full-path", "full-path-or-name"])
+                .hidden_short_help(true)
+                .long_help(
+                    "For symlink entries, match the search pattern against the symlink's \
+                         resolved target path instead of its own name. Useful for locating \
+                         links that point to a specific destination. Broken symlinks never \
+                         match under this mode, and non-symlink entries are unaffected.",
+                ),
+        )
         .arg(
             Arg::with_name("null_separator")
                 .long("print0")
@@ -182,8 +400,34 @@ pub fn build_app() -> App<'static, 'static> {
                          Useful for piping results to 'xargs'.",
                 ),
         )
+        .arg(
+            Arg::with_name("quote")
+                .long("quote")
+                .overrides_with("quote")
+                .conflicts_with_all(&["null_separator", "list-details"])
+                .hidden_short_help(true)
+                .help("Add single quotes around each path")
+                .long_help(
+                    "Quote each printed path with single quotes, escaping any single quotes \
+                         contained in the path itself. This makes it safe to use the output in a \
+                         shell, e.g. via '$(fd --quote)'.",
+                ),
+        )
+        .arg(
+            Arg::with_name("hyperlink")
+                .long("hyperlink")
+                .overrides_with("hyperlink")
+                .conflicts_with_all(&["quote", "format"])
+                .hidden_short_help(true)
+                .long_help(
+                    "Wrap each printed path in an OSC 8 hyperlink pointing to the file, for \
+                         terminals that support clickable links. Automatically disabled when \
+                         stdout is not a tty.",
+                ),
+        )
         .arg(
             Arg::with_name("max-depth")
+                .overrides_with("max-depth")
                 .long("max-depth")
                 .short("d")
                 .takes_value(true)
@@ -197,12 +441,26 @@ pub fn build_app() -> App<'static, 'static> {
         // support --maxdepth as well, for compatibility with rg
         .arg(
             Arg::with_name("rg-depth")
+                .overrides_with("rg-depth")
                 .long("maxdepth")
                 .hidden(true)
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("flat")
+                .long("flat")
+                .alias("no-recurse")
+                .conflicts_with_all(&["max-depth", "rg-depth", "exact-depth"])
+                .help("Do not descend into subdirectories (alias for '--max-depth=1')")
+                .long_help(
+                    "List only the direct contents of each search root, without descending into \
+                         subdirectories. This is equivalent to '--max-depth=1', spelled out for \
+                         discoverability.",
+                ),
+        )
         .arg(
             Arg::with_name("min-depth")
+                .overrides_with("min-depth")
                 .long("min-depth")
                 .takes_value(true)
                 .value_name("depth")
@@ -214,6 +472,7 @@ pub fn build_app() -> App<'static, 'static> {
         )
         .arg(
             Arg::with_name("exact-depth")
+                .overrides_with("exact-depth")
                 .long("exact-depth")
                 .takes_value(true)
                 .value_name("depth")
@@ -224,6 +483,24 @@ pub fn build_app() -> App<'static, 'static> {
                      '--min-depth <depth> --max-depth <depth>'.",
                 ),
         )
+        .arg(
+            Arg::with_name("depth-from")
+                .overrides_with("depth-from")
+                .long("depth-from")
+                .takes_value(true)
+                .value_name("point")
+                .possible_values(&["root", "cwd"])
+                .hide_possible_values(true)
+                .hidden_short_help(true)
+                .long_help(
+                    "Declare the reference point that '--max-depth', '--min-depth' and \
+                     '--exact-depth' count from, when multiple search roots are given at \
+                     different depths:\n  \
+                       'root':  count depth from each search root independently (default)\n  \
+                       'cwd':   count depth from the current working directory, regardless of \
+                                which search root an entry was found under",
+                ),
+        )
         .arg(
             Arg::with_name("prune")
                 .long("prune")
@@ -254,11 +531,15 @@ pub fn build_app() -> App<'static, 'static> {
                     "socket",
                     "p",
                     "pipe",
+                    "c",
+                    "char-device",
+                    "b",
+                    "block-device",
                 ])
                 .hide_possible_values(true)
                 .help(
                     "Filter by type: file (f), directory (d), symlink (l),\nexecutable (x), \
-                         empty (e), socket (s), pipe (p)",
+                         empty (e), socket (s), pipe (p), char-device (c), block-device (b)",
                 )
                 .long_help(
                     "Filter the search by type (multiple allowable filetypes can be specified):\n  \
@@ -268,7 +549,44 @@ pub fn build_app() -> App<'static, 'static> {
                        'x' or 'executable':   executables\n  \
                        'e' or 'empty':        empty files or directories\n  \
                        's' or 'socket':       socket\n  \
-                       'p' or 'pipe':         named pipe (FIFO)",
+                       'p' or 'pipe':         named pipe (FIFO)\n  \
+                       'c' or 'char-device':  character device (Unix only)\n  \
+                       'b' or 'block-device': block device (Unix only)",
+                ),
+        )
+        .arg(
+            Arg::with_name("type-not")
+                .long("type-not")
+                .multiple(true)
+                .number_of_values(1)
+                .takes_value(true)
+                .value_name("filetype")
+                .possible_values(&[
+                    "f",
+                    "file",
+                    "d",
+                    "directory",
+                    "l",
+                    "symlink",
+                    "x",
+                    "executable",
+                    "e",
+                    "empty",
+                    "s",
+                    "socket",
+                    "p",
+                    "pipe",
+                    "c",
+                    "char-device",
+                    "b",
+                    "block-device",
+                ])
+                .hide_possible_values(true)
+                .hidden_short_help(true)
+                .long_help(
+                    "Exclude a type from the search (multiple allowable filetypes can be \
+                         specified, using the same values as '--type'). Subtracted from whatever \
+                         '--type' would otherwise include.",
                 ),
         )
         .arg(
@@ -283,11 +601,33 @@ pub fn build_app() -> App<'static, 'static> {
                 .long_help(
                     "(Additionally) filter search results by their file extension. Multiple \
                      allowable file extensions can be specified.\n\
-                     If you want to search for files without extension, \
-                     you can use the regex '^[^.]+$' as a normal search pattern.",
+                     If you want to search for files without an extension, \
+                     pass an empty string ('--extension \"\"'); this can be combined with \
+                     other '--extension' values to match either. Alternatively, you can use \
+                     the regex '^[^.]+$' as a normal search pattern.",
                 ),
         )
         .arg(
+            Arg::with_name("extension-mode")
+                .overrides_with("extension-mode")
+                .long("extension-mode")
+                .takes_value(true)
+                .value_name("mode")
+                .possible_values(&["last", "any"])
+                .default_value("last")
+                .hidden_short_help(true)
+                .long_help(
+                    "Controls how '--extension' matches compound extensions such as 'tar.gz':\n  \
+                         'last': only the last dot-separated component has to match (default)\n  \
+                         'any':  any dot-separated suffix component may match, so e.g. \
+                         '--extension tar' also matches 'archive.tar.gz'",
+                ),
+        )
+        .arg(
+            // Unlike its sibling options, this isn't given `.overrides_with("exec")`: clap
+            // mishandles re-parsing an unbounded, terminator-delimited value list like this one
+            // when the same flag occurs twice, so a default-opts `--exec` would need its own
+            // `;` terminator embedded in the config anyway to be useful.
             Arg::with_name("exec")
                 .long("exec")
                 .short("x")
@@ -307,10 +647,15 @@ pub fn build_app() -> App<'static, 'static> {
                        '{/}':  basename\n  \
                        '{//}': parent directory\n  \
                        '{.}':  path without file extension\n  \
-                       '{/.}': basename without file extension",
+                       '{/.}': basename without file extension\n  \
+                       '{#}':  a 1-based index, incremented once per execution\n\n\
+                     Since commands run in parallel by default, '{#}' is assigned atomically but \
+                     the order in which indices reach the command is not guaranteed; pass \
+                     '--threads=1' if you need indices to be handed out in result order.",
                 ),
         )
         .arg(
+            // See the comment on '--exec' above for why this doesn't get '.overrides_with' too.
             Arg::with_name("exec-batch")
                 .long("exec-batch")
                 .short("X")
@@ -330,7 +675,44 @@ pub fn build_app() -> App<'static, 'static> {
                        '{/}':  basename\n  \
                        '{//}': parent directory\n  \
                        '{.}':  path without file extension\n  \
-                       '{/.}': basename without file extension",
+                       '{/.}': basename without file extension\n  \
+                       '{#}':  a 1-based index, always 1 since a single command is run",
+                ),
+        )
+        .arg(
+            Arg::with_name("exec-dry-run")
+                .long("exec-dry-run")
+                .short("n")
+                .hidden_short_help(true)
+                .long_help(
+                    "Print the command that would be executed for each search result, \
+                     shell-quoted, instead of running it. Only valid together with '--exec' \
+                     or '--exec-batch'.",
+                ),
+        )
+        .arg(
+            Arg::with_name("exec-cwd")
+                .long("exec-cwd")
+                .hidden_short_help(true)
+                .long_help(
+                    "Run the command in '--exec' with its working directory set to the search \
+                     result's parent directory, instead of fd's own working directory. A result \
+                     at the search root itself uses the search root as its parent. Only valid \
+                     together with '--exec'.",
+                ),
+        )
+        .arg(
+            Arg::with_name("exec-timeout")
+                .overrides_with("exec-timeout")
+                .long("exec-timeout")
+                .takes_value(true)
+                .value_name("dur")
+                .hidden_short_help(true)
+                .long_help(
+                    "Kill a command run by '--exec'/'--exec-batch' if it hasn't finished after \
+                     'dur'. A killed command counts as a failure for the purposes of fd's exit \
+                     code. The duration can be specified using common units, e.g. '500ms', \
+                     '10s', '2min'. Only valid together with '--exec' or '--exec-batch'.",
                 ),
         )
         .arg(
@@ -345,10 +727,68 @@ pub fn build_app() -> App<'static, 'static> {
                 .long_help(
                     "Exclude files/directories that match the given glob pattern. This \
                          overrides any other ignore logic. Multiple exclude patterns can be \
-                         specified.\n\n\
+                         specified. A single '{a,b}' alternation group is expanded before \
+                         matching, so e.g. '*.{tmp,bak}' excludes both extensions.\n\n\
                          Examples:\n  \
                            --exclude '*.pyc'\n  \
-                           --exclude node_modules",
+                           --exclude node_modules\n  \
+                           --exclude '*.{tmp,bak}'",
+                ),
+        )
+        .arg(
+            Arg::with_name("exclude-regex")
+                .long("exclude-regex")
+                .takes_value(true)
+                .value_name("pattern")
+                .number_of_values(1)
+                .multiple(true)
+                .help("Exclude entries that match the given regex pattern on the full path")
+                .long_help(
+                    "Exclude files/directories whose full (relative) path matches the given \
+                         regular expression. Unlike '--exclude', which is matched against glob \
+                         patterns component by component, this is matched against the entire \
+                         relative path at once. Multiple patterns can be specified.\n\n\
+                         Example:\n  \
+                           --exclude-regex '.*/target/.*'",
+                ),
+        )
+        .arg(
+            Arg::with_name("not")
+                .long("not")
+                .takes_value(true)
+                .value_name("pattern")
+                .number_of_values(1)
+                .multiple(true)
+                .help("Exclude entries that match the given regex pattern, complementing the search pattern")
+                .long_help(
+                    "Exclude entries whose name (or full path, depending on '--full-path'/\
+                         '--full-path-or-name') matches the given regular expression, \
+                         complementing the search pattern. Unlike '--exclude-regex', which is \
+                         always matched against the full relative path, '--not' is matched the \
+                         same way as the search pattern itself, and its case sensitivity \
+                         follows the same rules ('--case-sensitive', '--ignore-case', smart \
+                         case). Multiple patterns can be specified; an entry is excluded if any \
+                         of them match.\n\n\
+                         Example:\n  \
+                           fd . --not '\\.bak$'",
+                ),
+        )
+        .arg(
+            Arg::with_name("and")
+                .long("and")
+                .takes_value(true)
+                .value_name("pattern")
+                .number_of_values(1)
+                .multiple(true)
+                .help("Additional required search patterns, all of which must match")
+                .long_help(
+                    "Add additional required search patterns, all of which must match, along \
+                         with the primary pattern. This is a convenient alternative to writing a \
+                         single pattern with lookahead assertions. Multiple '--and' flags can be \
+                         given. Each pattern is treated the same way as the primary pattern \
+                         (respecting '--glob', '--fixed-strings', '--case-sensitive', etc.)\n\n\
+                         Example:\n  \
+                           fd needle1 --and needle2 --and needle3",
                 ),
         )
         .arg(
@@ -364,24 +804,51 @@ pub fn build_app() -> App<'static, 'static> {
                          precedence.",
                 ),
         )
+        .arg(
+            Arg::with_name("ignore-file-name")
+                .long("ignore-file-name")
+                .takes_value(true)
+                .value_name("name")
+                .number_of_values(1)
+                .multiple(true)
+                .hidden_short_help(true)
+                .validator(|v| {
+                    if Path::new(&v).file_name().map_or(false, |name| name == v.as_str()) {
+                        Ok(())
+                    } else {
+                        Err(format!("'{}' is not a valid file name", v))
+                    }
+                })
+                .long_help(
+                    "Enable a custom ignore-filename, in addition to the built-in '.gitignore' \
+                         (and '.fdignore'). Can be repeated to register multiple filenames, e.g. \
+                         '.ignore' or '.rgignore'. Must be a plain file name, not a path.",
+                ),
+        )
         .arg(
             Arg::with_name("color")
+                .overrides_with("color")
                 .long("color")
                 .short("c")
                 .takes_value(true)
                 .value_name("when")
-                .possible_values(&["never", "auto", "always"])
+                .possible_values(&["never", "auto", "always", "force"])
                 .hide_possible_values(true)
                 .help("When to use colors: never, *auto*, always")
                 .long_help(
                     "Declare when to use color for the pattern match output:\n  \
-                       'auto':      show colors if the output goes to an interactive console (default)\n  \
+                       'auto':      show colors if the output goes to an interactive console, \
+                         or if the 'FORCE_COLOR' environment variable is set to a value other \
+                         than '0' (default)\n  \
                        'never':     do not use colorized output\n  \
-                       'always':    always use colorized output",
+                       'always':    always use colorized output\n  \
+                       'force':     alias for 'always', for compatibility with tools that use that name\n\n\
+                       The 'NO_COLOR' environment variable, if set, disables color in 'auto' mode.",
                 ),
         )
         .arg(
             Arg::with_name("threads")
+                .overrides_with("threads")
                 .long("threads")
                 .short("j")
                 .takes_value(true)
@@ -389,7 +856,21 @@ pub fn build_app() -> App<'static, 'static> {
                 .hidden_short_help(true)
                 .long_help(
                     "Set number of threads to use for searching & executing (default: number \
-                         of available CPU cores)",
+                         of available CPU cores). A value of '0' is treated as the default.",
+                ),
+        )
+        .arg(
+            Arg::with_name("threads-ratio")
+                .overrides_with("threads-ratio")
+                .long("threads-ratio")
+                .takes_value(true)
+                .value_name("ratio")
+                .conflicts_with("threads")
+                .hidden_short_help(true)
+                .long_help(
+                    "Set number of threads to use for searching & executing as a ratio of the \
+                         number of available CPU cores, e.g. '0.5' to use half of them. The \
+                         result is rounded and always at least 1.",
                 ),
         )
         .arg(
@@ -422,16 +903,34 @@ pub fn build_app() -> App<'static, 'static> {
         )
         .arg(
             Arg::with_name("max-buffer-time")
+                .overrides_with("max-buffer-time")
                 .long("max-buffer-time")
                 .takes_value(true)
                 .hidden(true)
                 .long_help(
                     "Amount of time in milliseconds to buffer, before streaming the search \
-                         results to the console.",
+                         results to the console. Set to '0' to skip this buffering window and \
+                         stream each result in the order it is found; the output itself is \
+                         still written through a buffered writer, which '--no-buffer' flushes \
+                         after every result.",
+                ),
+        )
+        .arg(
+            Arg::with_name("no-buffer")
+                .long("no-buffer")
+                .overrides_with("no-buffer")
+                .hidden_short_help(true)
+                .help("Flush output after every result, bypassing the internal buffering")
+                .long_help(
+                    "Flush the output after every single result, bypassing the internal \
+                     buffering entirely instead of just shortening it like \
+                     '--max-buffer-time 0' does. Useful when piping into a line-by-line \
+                     consumer that needs to see each result as soon as it is found.",
                 ),
         )
         .arg(
             Arg::with_name("changed-within")
+                .overrides_with("changed-within")
                 .long("changed-within")
                 .alias("change-newer-than")
                 .alias("newer")
@@ -452,6 +951,7 @@ pub fn build_app() -> App<'static, 'static> {
         )
         .arg(
             Arg::with_name("changed-before")
+                .overrides_with("changed-before")
                 .long("changed-before")
                 .alias("change-older-than")
                 .alias("older")
@@ -469,8 +969,71 @@ pub fn build_app() -> App<'static, 'static> {
                          --older 2018-10-27",
                 ),
         )
+        .arg(
+            Arg::with_name("newer-than")
+                .overrides_with("newer-than")
+                .long("newer-than")
+                .takes_value(true)
+                .value_name("path")
+                .number_of_values(1)
+                .hidden_short_help(true)
+                .help("Filter by file modification time (newer than a reference file)")
+                .long_help(
+                    "Filter results based on the file modification time, using the \
+                     modification time of the given reference file as the cutoff, like \
+                     `find -newer`. The reference file must exist.\n\
+                     Example:\n    \
+                         --newer-than reference.txt",
+                ),
+        )
+        .arg(
+            Arg::with_name("older-than")
+                .overrides_with("older-than")
+                .long("older-than")
+                .takes_value(true)
+                .value_name("path")
+                .number_of_values(1)
+                .hidden_short_help(true)
+                .help("Filter by file modification time (older than a reference file)")
+                .long_help(
+                    "Filter results based on the file modification time, using the \
+                     modification time of the given reference file as the cutoff. The \
+                     reference file must exist.\n\
+                     Example:\n    \
+                         --older-than reference.txt",
+                ),
+        )
+        .arg(
+            Arg::with_name("sort")
+                .overrides_with("sort")
+                .long("sort")
+                .takes_value(true)
+                .value_name("criteria")
+                .possible_values(&["path", "name", "size", "modified"])
+                .hide_possible_values(true)
+                .conflicts_with_all(&["exec", "exec-batch"])
+                .help("Sort the results by the given criteria")
+                .long_help(
+                    "Sort the results by the given criteria:\n  \
+                       'path':      the full path (default order otherwise)\n  \
+                       'name':      the filename\n  \
+                       'size':      the file size\n  \
+                       'modified':  the last modification time\n\
+                     This forces fd to collect all results before printing them, so search \
+                     results will no longer be streamed to the console while searching.",
+                ),
+        )
+        .arg(
+            Arg::with_name("sort-reverse")
+                .long("sort-reverse")
+                .requires("sort")
+                .hidden_short_help(true)
+                .help("Reverse the order of --sort")
+                .long_help("Reverse the order produced by '--sort'."),
+        )
         .arg(
             Arg::with_name("max-results")
+                .overrides_with("max-results")
                 .long("max-results")
                 .takes_value(true)
                 .value_name("count")
@@ -486,6 +1049,7 @@ pub fn build_app() -> App<'static, 'static> {
         .arg(
             Arg::with_name("max-one-result")
                 .short("1")
+                .long("first")
                 .hidden_short_help(true)
                 .overrides_with("max-results")
                 .conflicts_with_all(&["exec", "exec-batch", "list-details"])
@@ -499,11 +1063,63 @@ pub fn build_app() -> App<'static, 'static> {
                 .overrides_with("show-errors")
                 .long_help(
                     "Enable the display of filesystem errors for situations such as \
-                         insufficient permissions or dead symlinks.",
+                         insufficient permissions or dead symlinks. Without this flag, fd \
+                         only prints a single line with the total number of errors \
+                         encountered, if any.",
+                ),
+        )
+        .arg(
+            Arg::with_name("count")
+                .long("count")
+                .conflicts_with_all(&["exec", "exec-batch", "list-details"])
+                .hidden_short_help(true)
+                .long_help(
+                    "Suppress per-entry output and print only the total number of matches once \
+                         the search has finished. All filters ('--type', '--size', etc.) are \
+                         still applied beforehand. Can be combined with '--stats', which still \
+                         prints its summary to stderr.",
+                ),
+        )
+        .arg(
+            Arg::with_name("quiet")
+                .long("quiet")
+                .short("q")
+                .overrides_with("quiet")
+                .hidden_short_help(true)
+                .help("Print nothing to stderr")
+                .long_help(
+                    "Suppress all diagnostic messages, such as filesystem errors or invalid \
+                         search paths, that would otherwise be printed to stderr. This takes \
+                         precedence over '--show-errors'. The exit code still reflects whether \
+                         an error occurred.",
+                ),
+        )
+        .arg(
+            Arg::with_name("stats")
+                .long("stats")
+                .overrides_with("stats")
+                .hidden_short_help(true)
+                .long_help(
+                    "Print a summary of the search (matches broken down by file type, \
+                         directories visited, and elapsed time) to stderr after the search has \
+                         completed. Has no effect on stdout, so output can still be piped.",
+                ),
+        )
+        .arg(
+            Arg::with_name("progress")
+                .long("progress")
+                .overrides_with("progress")
+                .hidden_short_help(true)
+                .long_help(
+                    "While the search is running and stderr is a terminal, periodically print \
+                         the number of directories visited so far to stderr, overwriting the \
+                         same line. The line is cleared once the search completes. Has no \
+                         effect on stdout, so output can still be piped.",
                 ),
         )
         .arg(
             Arg::with_name("base-directory")
+                .overrides_with("base-directory")
                 .long("base-directory")
                 .takes_value(true)
                 .value_name("path")
@@ -517,6 +1133,21 @@ pub fn build_app() -> App<'static, 'static> {
                          relative to this directory.",
                 ),
         )
+        .arg(
+            Arg::with_name("pattern-file")
+                .overrides_with("pattern-file")
+                .long("pattern-file")
+                .takes_value(true)
+                .value_name("path")
+                .conflicts_with("pattern")
+                .hidden_short_help(true)
+                .long_help(
+                    "Read the search pattern from the given file instead of the positional \
+                         <pattern> argument, trimming a trailing newline. Pass '-' to read the \
+                         pattern from stdin. Useful for long or generated regular expressions \
+                         that would otherwise require shell quoting.",
+                ),
+        )
         .arg(
             Arg::with_name("pattern").help(
                 "the search pattern (a regular expression, unless '--glob' is used; optional)",
@@ -528,6 +1159,7 @@ pub fn build_app() -> App<'static, 'static> {
         )
         .arg(
             Arg::with_name("path-separator")
+                .overrides_with("path-separator")
                 .takes_value(true)
                 .value_name("separator")
                 .long("path-separator")
@@ -549,21 +1181,23 @@ pub fn build_app() -> App<'static, 'static> {
         .arg(
             Arg::with_name("search-path")
                 .long("search-path")
+                .short("P")
                 .takes_value(true)
-                .conflicts_with("path")
                 .multiple(true)
                 .hidden_short_help(true)
                 .number_of_values(1)
                 .long_help(
                     "Provide paths to search as an alternative to the positional <path> \
                          argument. Changes the usage to `fd [FLAGS/OPTIONS] --search-path <path> \
-                         --search-path <path2> [<pattern>]`",
+                         --search-path <path2> [<pattern>]`. Can be combined with the \
+                         positional <path> argument(s); all of them are searched.",
                 ),
         );
 
     if cfg!(unix) {
         app = app.arg(
             Arg::with_name("owner")
+                .overrides_with("owner")
                 .long("owner")
                 .short("o")
                 .takes_value(true)
@@ -579,6 +1213,42 @@ pub fn build_app() -> App<'static, 'static> {
                          --owner '!john:students'",
                 ),
         );
+
+        app = app.arg(
+            Arg::with_name("perm")
+                .overrides_with("perm")
+                .long("perm")
+                .takes_value(true)
+                .value_name("mode")
+                .allow_hyphen_values(true)
+                .help("Filter by permission bits")
+                .long_help(
+                    "Filter files by their permission bits. 'mode' can be an octal number \
+                     (e.g. '644') or a symbolic mode (e.g. 'u+rwx,g+rx,o+r'). By default, the \
+                     permission bits must match 'mode' exactly. Prefix 'mode' with '-' to \
+                     require all of its bits to be set, or with '/' to require any of them to \
+                     be set.\n\
+                     Examples:\n    \
+                         --perm 644\n    \
+                         --perm -u+x\n    \
+                         --perm /o+w",
+                ),
+        );
+
+        app = app.arg(
+            Arg::with_name("same-file-as")
+                .overrides_with("same-file-as")
+                .long("same-file-as")
+                .takes_value(true)
+                .value_name("path")
+                .hidden_short_help(true)
+                .help("Filter by files that are hardlinks to <path>")
+                .long_help(
+                    "Filter files by whether they are hardlinks to the given reference file, \
+                     i.e. whether they share the same device and inode number. The reference \
+                     file itself is included in the results.",
+                ),
+        );
     }
 
     // Make `--one-file-system` available only on Unix and Windows platforms, as per the
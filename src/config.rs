@@ -0,0 +1,75 @@
+use std::ffi::OsString;
+use std::fs;
+use std::io::ErrorKind;
+
+use anyhow::{anyhow, Context, Result};
+use toml::Value;
+
+/// Reads `<config_dir>/fd/config.toml` (e.g. `~/.config/fd/config.toml` on Linux) and converts
+/// its top-level keys into the equivalent command-line flags, one key per long flag name. Returns
+/// an empty list if no config file is present, so it can be unconditionally spliced into the
+/// process's own arguments.
+pub fn args_from_config_file() -> Result<Vec<OsString>> {
+    let config_path = match dirs_next::config_dir() {
+        Some(dir) => dir.join("fd").join("config.toml"),
+        None => return Ok(Vec::new()),
+    };
+
+    let contents = match fs::read_to_string(&config_path) {
+        Ok(contents) => contents,
+        Err(ref err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => {
+            return Err(err)
+                .with_context(|| format!("Failed to read config file '{}'", config_path.display()))
+        }
+    };
+
+    let config: Value = toml::from_str(&contents)
+        .with_context(|| format!("Failed to parse config file '{}'", config_path.display()))?;
+
+    let table = config.as_table().ok_or_else(|| {
+        anyhow!(
+            "Config file '{}' must be a table mapping option names to values",
+            config_path.display()
+        )
+    })?;
+
+    let mut args = Vec::new();
+    for (key, value) in table {
+        let flag = OsString::from(format!("--{}", key));
+        match value {
+            Value::Boolean(true) => args.push(flag),
+            Value::Boolean(false) => {}
+            Value::String(s) => {
+                args.push(flag);
+                args.push(OsString::from(s));
+            }
+            Value::Integer(i) => {
+                args.push(flag);
+                args.push(OsString::from(i.to_string()));
+            }
+            Value::Array(values) => {
+                for value in values {
+                    let value = value.as_str().ok_or_else(|| {
+                        anyhow!(
+                            "Config file '{}': array values for '{}' must be strings",
+                            config_path.display(),
+                            key
+                        )
+                    })?;
+                    args.push(flag.clone());
+                    args.push(OsString::from(value));
+                }
+            }
+            _ => {
+                return Err(anyhow!(
+                    "Config file '{}': unsupported value for '{}'",
+                    config_path.display(),
+                    key
+                ))
+            }
+        }
+    }
+
+    Ok(args)
+}
@@ -1,13 +1,143 @@
 use std::io;
-use std::io::Write;
-use std::process::Command;
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
 use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[cfg(unix)]
+use std::os::unix::process::CommandExt;
 
 use crate::error::print_error;
 use crate::exit_codes::ExitCode;
 
-/// Executes a command.
-pub fn execute_command(mut cmd: Command, out_perm: &Mutex<()>) -> ExitCode {
+/// How often to poll a child process for exit, while waiting for it to finish within
+/// '--exec-timeout'.
+const TIMEOUT_POLL_INTERVAL: Duration = Duration::from_millis(25);
+
+/// Kill the whole process group of a timed-out child, not just the immediate child itself: a
+/// shell wrapper like `sh -c "sleep 5"` would otherwise keep its own child running (and its end
+/// of our stdout/stderr pipes open) even after the shell itself has been killed.
+#[cfg(all(unix, not(target_os = "redox")))]
+fn kill_process_tree(child: &mut std::process::Child) {
+    unsafe {
+        libc::killpg(child.id() as libc::pid_t, libc::SIGKILL);
+    }
+    let _ = child.kill();
+}
+
+#[cfg(not(all(unix, not(target_os = "redox"))))]
+fn kill_process_tree(child: &mut std::process::Child) {
+    let _ = child.kill();
+}
+
+/// Prints the command line that would be executed, quoted so that it could be pasted into a
+/// shell, instead of actually running it.
+pub fn print_command(cmd: &Command, out_perm: &Mutex<()>) -> ExitCode {
+    let words = std::iter::once(cmd.get_program())
+        .chain(cmd.get_args())
+        .map(|arg| arg.to_string_lossy());
+    let line = shell_words::join(words);
+
+    // While this lock is active, this thread will be the only thread allowed to write its
+    // output, keeping dry-run lines from different workers from interleaving.
+    let _lock = out_perm.lock().unwrap();
+    println!("{}", line);
+
+    ExitCode::Success
+}
+
+/// Executes a command. If `timeout` is given, the command is killed (and counts as a failure)
+/// if it hasn't exited within that duration.
+pub fn execute_command(mut cmd: Command, out_perm: &Mutex<()>, timeout: Option<Duration>) -> ExitCode {
+    let timeout = match timeout {
+        Some(timeout) => timeout,
+        None => return execute_command_untimed(cmd, out_perm),
+    };
+
+    #[cfg(unix)]
+    cmd.process_group(0);
+
+    let mut child = match cmd.stdout(Stdio::piped()).stderr(Stdio::piped()).spawn() {
+        Ok(child) => child,
+        Err(ref why) if why.kind() == io::ErrorKind::NotFound => {
+            print_error(format!("Command not found: {:?}", cmd));
+            return ExitCode::GeneralError;
+        }
+        Err(why) => {
+            print_error(format!("Problem while executing command: {}", why));
+            return ExitCode::GeneralError;
+        }
+    };
+
+    // The child's stdout/stderr pipes need to be drained concurrently with waiting for it to
+    // exit, otherwise a chatty child can fill the OS pipe buffer and deadlock against us.
+    let mut stdout_pipe = child.stdout.take().expect("child was spawned with a stdout pipe");
+    let mut stderr_pipe = child.stderr.take().expect("child was spawned with a stderr pipe");
+    let stdout_reader = thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = stdout_pipe.read_to_end(&mut buf);
+        buf
+    });
+    let stderr_reader = thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = stderr_pipe.read_to_end(&mut buf);
+        buf
+    });
+
+    let start = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Some(status),
+            Ok(None) => {
+                if start.elapsed() >= timeout {
+                    break None;
+                }
+                thread::sleep(TIMEOUT_POLL_INTERVAL);
+            }
+            Err(why) => {
+                print_error(format!("Problem while waiting for command: {}", why));
+                return ExitCode::GeneralError;
+            }
+        }
+    };
+
+    let status = match status {
+        Some(status) => status,
+        None => {
+            // The reader threads are intentionally left unjoined here: they're blocked until
+            // every holder of a pipe write-end exits, which `kill_process_tree` takes care of,
+            // but there's no point waiting on them since we're discarding the timed-out command's
+            // output anyway.
+            kill_process_tree(&mut child);
+            let _ = child.wait();
+            print_error(format!(
+                "Command timed out after {:?} and was killed: {:?}",
+                timeout, cmd
+            ));
+            return ExitCode::GeneralError;
+        }
+    };
+
+    let stdout = stdout_reader.join().unwrap_or_default();
+    let stderr = stderr_reader.join().unwrap_or_default();
+
+    // While this lock is active, this thread will be the only thread allowed to write its
+    // outputs.
+    let _lock = out_perm.lock().unwrap();
+
+    let _ = io::stdout().lock().write_all(&stdout);
+    let _ = io::stderr().lock().write_all(&stderr);
+
+    if status.code() == Some(0) {
+        ExitCode::Success
+    } else {
+        ExitCode::GeneralError
+    }
+}
+
+/// Executes a command without enforcing a timeout.
+fn execute_command_untimed(mut cmd: Command, out_perm: &Mutex<()>) -> ExitCode {
     // Spawn the supplied command.
     let output = cmd.output();
 
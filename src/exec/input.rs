@@ -57,6 +57,7 @@ mod path_tests {
         remove_ext_simple:  remove_extension  for  "foo.txt"      =>  "foo"
         remove_ext_dir:     remove_extension  for  "dir/foo.txt"  =>  "dir/foo"
         hidden:             remove_extension  for  ".foo"         =>  ".foo"
+        hidden_dir:         remove_extension  for  "dir/.foo"     =>  "dir/.foo"
         remove_ext_utf8:    remove_extension  for  "💖.txt"       =>  "💖"
         remove_ext_empty:   remove_extension  for  ""             =>  ""
 
@@ -7,7 +7,9 @@ use std::borrow::Cow;
 use std::ffi::{OsStr, OsString};
 use std::path::{Component, Path, PathBuf, Prefix};
 use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use anyhow::{anyhow, Result};
 use lazy_static::lazy_static;
@@ -16,7 +18,7 @@ use regex::Regex;
 use crate::exit_codes::ExitCode;
 use crate::filesystem::strip_current_dir;
 
-use self::command::execute_command;
+use self::command::{execute_command, print_command};
 use self::input::{basename, dirname, remove_extension};
 pub use self::job::{batch, job};
 use self::token::Token;
@@ -34,11 +36,34 @@ pub enum ExecutionMode {
 ///
 /// The template is meant to be coupled with an input in order to generate a command. The
 /// `generate_and_execute()` method will be used to generate a command and execute it.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub struct CommandTemplate {
     args: Vec<ArgumentTemplate>,
     mode: ExecutionMode,
     path_separator: Option<String>,
+    dry_run: bool,
+    exec_cwd: bool,
+    /// Shared, atomically-incremented counter backing the `{#}` placeholder. Since `--exec`
+    /// commands usually run in parallel, the order in which a given index reaches a command is
+    /// not guaranteed unless execution is forced to be sequential (`--threads=1`); only the
+    /// assignment of each index is guaranteed to be unique.
+    next_index: Arc<AtomicUsize>,
+    /// If set, a command that hasn't exited within this duration is killed and counted as a
+    /// failure, instead of being allowed to run indefinitely.
+    timeout: Option<Duration>,
+}
+
+// The execution counter is deliberately excluded: two templates built from the same input
+// represent the same command regardless of how many times either has since been executed.
+impl PartialEq for CommandTemplate {
+    fn eq(&self, other: &Self) -> bool {
+        self.args == other.args
+            && self.mode == other.mode
+            && self.path_separator == other.path_separator
+            && self.dry_run == other.dry_run
+            && self.exec_cwd == other.exec_cwd
+            && self.timeout == other.timeout
+    }
 }
 
 impl CommandTemplate {
@@ -73,7 +98,7 @@ impl CommandTemplate {
         S: AsRef<str>,
     {
         lazy_static! {
-            static ref PLACEHOLDER_PATTERN: Regex = Regex::new(r"\{(/?\.?|//)\}").unwrap();
+            static ref PLACEHOLDER_PATTERN: Regex = Regex::new(r"\{(/?\.?|//|#)\}").unwrap();
         }
 
         let mut args = Vec::new();
@@ -99,10 +124,15 @@ impl CommandTemplate {
                     "{/}" => tokens.push(Token::Basename),
                     "{//}" => tokens.push(Token::Parent),
                     "{/.}" => tokens.push(Token::BasenameNoExt),
+                    "{#}" => tokens.push(Token::Index),
                     _ => unreachable!("Unhandled placeholder"),
                 }
 
-                has_placeholder = true;
+                // '{#}' doesn't refer to the search result's path, so unlike the other
+                // placeholders it shouldn't suppress the implicit trailing '{}' below.
+                if placeholder.as_str() != "{#}" {
+                    has_placeholder = true;
+                }
             }
 
             // Without a placeholder, the argument is just fixed text.
@@ -128,9 +158,32 @@ impl CommandTemplate {
             args,
             mode,
             path_separator,
+            dry_run: false,
+            exec_cwd: false,
+            next_index: Arc::new(AtomicUsize::new(1)),
+            timeout: None,
         }
     }
 
+    /// Print the fully-substituted command line instead of executing it.
+    pub fn dry_run(mut self, dry_run: bool) -> CommandTemplate {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Run the command with its current directory set to the match's parent directory, instead
+    /// of inheriting fd's own current directory.
+    pub fn exec_cwd(mut self, exec_cwd: bool) -> CommandTemplate {
+        self.exec_cwd = exec_cwd;
+        self
+    }
+
+    /// Kill the command (counting it as a failure) if it hasn't exited within `timeout`.
+    pub fn timeout(mut self, timeout: Option<Duration>) -> CommandTemplate {
+        self.timeout = timeout;
+        self
+    }
+
     fn number_of_tokens(&self) -> usize {
         self.args.iter().filter(|arg| arg.has_tokens()).count()
     }
@@ -140,14 +193,30 @@ impl CommandTemplate {
     /// Using the internal `args` field, and a supplied `input` variable, a `Command` will be
     /// build. Once all arguments have been processed, the command is executed.
     pub fn generate_and_execute(&self, input: &Path, out_perm: Arc<Mutex<()>>) -> ExitCode {
+        // Files at the root have the search root ('.') as their parent.
+        let exec_cwd = self.exec_cwd.then(|| input.parent().unwrap_or(Path::new(".")));
+
         let input = strip_current_dir(input);
+        let index = self.next_index.fetch_add(1, Ordering::Relaxed);
 
-        let mut cmd = Command::new(self.args[0].generate(&input, self.path_separator.as_deref()));
+        let mut cmd = Command::new(self.args[0].generate(
+            &input,
+            self.path_separator.as_deref(),
+            index,
+        ));
         for arg in &self.args[1..] {
-            cmd.arg(arg.generate(&input, self.path_separator.as_deref()));
+            cmd.arg(arg.generate(&input, self.path_separator.as_deref(), index));
         }
 
-        execute_command(cmd, &out_perm)
+        if let Some(exec_cwd) = exec_cwd {
+            cmd.current_dir(exec_cwd);
+        }
+
+        if self.dry_run {
+            print_command(&cmd, &out_perm)
+        } else {
+            execute_command(cmd, &out_perm, self.timeout)
+        }
     }
 
     pub fn in_batch_mode(&self) -> bool {
@@ -158,7 +227,9 @@ impl CommandTemplate {
     where
         I: Iterator<Item = PathBuf>,
     {
-        let mut cmd = Command::new(self.args[0].generate("", None));
+        let index = self.next_index.fetch_add(1, Ordering::Relaxed);
+
+        let mut cmd = Command::new(self.args[0].generate("", None, index));
         cmd.stdin(Stdio::inherit());
         cmd.stdout(Stdio::inherit());
         cmd.stderr(Stdio::inherit());
@@ -173,18 +244,24 @@ impl CommandTemplate {
                 // A single `Tokens` is expected
                 // So we can directly consume the iterator once and for all
                 for path in &mut paths {
-                    cmd.arg(arg.generate(strip_current_dir(path), self.path_separator.as_deref()));
+                    cmd.arg(arg.generate(
+                        strip_current_dir(path),
+                        self.path_separator.as_deref(),
+                        index,
+                    ));
                     has_path = true;
                 }
             } else {
-                cmd.arg(arg.generate("", None));
+                cmd.arg(arg.generate("", None, index));
             }
         }
 
-        if has_path {
-            execute_command(cmd, &Mutex::new(()))
-        } else {
+        if !has_path {
             ExitCode::Success
+        } else if self.dry_run {
+            print_command(&cmd, &Mutex::new(()))
+        } else {
+            execute_command(cmd, &Mutex::new(()), self.timeout)
         }
     }
 }
@@ -209,8 +286,14 @@ impl ArgumentTemplate {
 
     /// Generate an argument from this template. If path_separator is Some, then it will replace
     /// the path separator in all placeholder tokens. Text arguments and tokens are not affected by
-    /// path separator substitution.
-    pub fn generate(&self, path: impl AsRef<Path>, path_separator: Option<&str>) -> OsString {
+    /// path separator substitution. `index` is the 1-based execution count, substituted for any
+    /// `{#}` token.
+    pub fn generate(
+        &self,
+        path: impl AsRef<Path>,
+        path_separator: Option<&str>,
+        index: usize,
+    ) -> OsString {
         use self::Token::*;
         let path = path.as_ref();
 
@@ -232,6 +315,7 @@ impl ArgumentTemplate {
                         Placeholder => {
                             s.push(Self::replace_separator(path.as_ref(), path_separator))
                         }
+                        Index => s.push(index.to_string()),
                         Text(ref string) => s.push(string),
                     }
                 }
@@ -313,6 +397,10 @@ mod tests {
                 ],
                 mode: ExecutionMode::OneByOne,
                 path_separator: None,
+                dry_run: false,
+                exec_cwd: false,
+                next_index: Arc::new(AtomicUsize::new(1)),
+                timeout: None,
             }
         );
     }
@@ -328,6 +416,10 @@ mod tests {
                 ],
                 mode: ExecutionMode::OneByOne,
                 path_separator: None,
+                dry_run: false,
+                exec_cwd: false,
+                next_index: Arc::new(AtomicUsize::new(1)),
+                timeout: None,
             }
         );
     }
@@ -343,6 +435,10 @@ mod tests {
                 ],
                 mode: ExecutionMode::OneByOne,
                 path_separator: None,
+                dry_run: false,
+                exec_cwd: false,
+                next_index: Arc::new(AtomicUsize::new(1)),
+                timeout: None,
             }
         );
     }
@@ -358,6 +454,10 @@ mod tests {
                 ],
                 mode: ExecutionMode::OneByOne,
                 path_separator: None,
+                dry_run: false,
+                exec_cwd: false,
+                next_index: Arc::new(AtomicUsize::new(1)),
+                timeout: None,
             }
         );
     }
@@ -373,6 +473,10 @@ mod tests {
                 ],
                 mode: ExecutionMode::OneByOne,
                 path_separator: None,
+                dry_run: false,
+                exec_cwd: false,
+                next_index: Arc::new(AtomicUsize::new(1)),
+                timeout: None,
             }
         );
     }
@@ -392,6 +496,10 @@ mod tests {
                 ],
                 mode: ExecutionMode::OneByOne,
                 path_separator: None,
+                dry_run: false,
+                exec_cwd: false,
+                next_index: Arc::new(AtomicUsize::new(1)),
+                timeout: None,
             }
         );
     }
@@ -407,6 +515,10 @@ mod tests {
                 ],
                 mode: ExecutionMode::Batch,
                 path_separator: None,
+                dry_run: false,
+                exec_cwd: false,
+                next_index: Arc::new(AtomicUsize::new(1)),
+                timeout: None,
             }
         );
     }
@@ -421,7 +533,7 @@ mod tests {
         let arg = ArgumentTemplate::Tokens(vec![Token::Placeholder]);
         macro_rules! check {
             ($input:expr, $expected:expr) => {
-                assert_eq!(arg.generate($input, Some("#")), OsString::from($expected));
+                assert_eq!(arg.generate($input, Some("#"), 1), OsString::from($expected));
             };
         }
 
@@ -430,13 +542,22 @@ mod tests {
         check!("/foo/bar/baz", "#foo#bar#baz");
     }
 
+    #[test]
+    fn next_index_starts_at_one_and_increments() {
+        let cmd = CommandTemplate::new(&["echo", "{#}"], None);
+        for expected_index in 1..=3 {
+            let index = cmd.next_index.fetch_add(1, Ordering::Relaxed);
+            assert_eq!(index, expected_index);
+        }
+    }
+
     #[cfg(windows)]
     #[test]
     fn generate_custom_path_separator_windows() {
         let arg = ArgumentTemplate::Tokens(vec![Token::Placeholder]);
         macro_rules! check {
             ($input:expr, $expected:expr) => {
-                assert_eq!(arg.generate($input, Some("#")), OsString::from($expected));
+                assert_eq!(arg.generate($input, Some("#"), 1), OsString::from($expected));
             };
         }
 
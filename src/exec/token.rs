@@ -11,6 +11,8 @@ pub enum Token {
     Parent,
     NoExt,
     BasenameNoExt,
+    /// A 1-based index, incremented once per command execution.
+    Index,
     Text(String),
 }
 
@@ -22,6 +24,7 @@ impl Display for Token {
             Token::Parent => f.write_str("{//}")?,
             Token::NoExt => f.write_str("{.}")?,
             Token::BasenameNoExt => f.write_str("{/.}")?,
+            Token::Index => f.write_str("{#}")?,
             Token::Text(ref string) => f.write_str(string)?,
         }
         Ok(())
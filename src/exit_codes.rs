@@ -1,6 +1,7 @@
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ExitCode {
     Success,
+    NoMatchesFound,
     GeneralError,
     KilledBySigint,
 }
@@ -9,7 +10,8 @@ impl Into<i32> for ExitCode {
     fn into(self) -> i32 {
         match self {
             ExitCode::Success => 0,
-            ExitCode::GeneralError => 1,
+            ExitCode::NoMatchesFound => 1,
+            ExitCode::GeneralError => 2,
             ExitCode::KilledBySigint => 130,
         }
     }
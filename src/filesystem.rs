@@ -20,17 +20,71 @@ pub fn path_absolute_form(path: &Path) -> io::Result<PathBuf> {
 
 pub fn absolute_path(path: &Path) -> io::Result<PathBuf> {
     let path_buf = path_absolute_form(path)?;
+    Ok(strip_long_path_prefix(&path_buf).into_owned())
+}
+
+/// Prefixes an absolute path with the `\\?\` extended-length marker (or `\\?\UNC\` for UNC
+/// paths), if it doesn't already have one, so that the filesystem APIs used during the walk
+/// aren't subject to Windows' legacy 260-character `MAX_PATH` limit. A no-op on other platforms.
+#[cfg(windows)]
+pub fn ensure_long_path_prefix(path: PathBuf) -> PathBuf {
+    let path_str = path.to_string_lossy();
+    if path_str.starts_with(r"\\?\") {
+        path
+    } else if let Some(unc_path) = path_str.strip_prefix(r"\\") {
+        PathBuf::from(format!(r"\\?\UNC\{}", unc_path))
+    } else {
+        PathBuf::from(format!(r"\\?\{}", path_str))
+    }
+}
 
-    #[cfg(windows)]
-    let path_buf = Path::new(
-        path_buf
-            .as_path()
-            .to_string_lossy()
-            .trim_start_matches(r"\\?\"),
-    )
-    .to_path_buf();
+#[cfg(not(windows))]
+pub fn ensure_long_path_prefix(path: PathBuf) -> PathBuf {
+    path
+}
+
+/// Strips the `\\?\` extended-length prefix back off a path (added by `ensure_long_path_prefix`,
+/// or present because the path came from `Path::canonicalize`, which returns verbatim paths on
+/// Windows), so it's fit for pattern matching and for display. A no-op on other platforms.
+#[cfg(windows)]
+pub fn strip_long_path_prefix(path: &Path) -> Cow<Path> {
+    let path_str = path.to_string_lossy();
+    if let Some(unc_path) = path_str.strip_prefix(r"\\?\UNC\") {
+        Cow::Owned(PathBuf::from(format!(r"\\{}", unc_path)))
+    } else if let Some(rest) = path_str.strip_prefix(r"\\?\") {
+        Cow::Owned(PathBuf::from(rest.to_string()))
+    } else {
+        Cow::Borrowed(path)
+    }
+}
 
-    Ok(path_buf)
+#[cfg(not(windows))]
+pub fn strip_long_path_prefix(path: &Path) -> Cow<Path> {
+    Cow::Borrowed(path)
+}
+
+/// Returns the canonical, absolute form of `path`, resolving symlinks and `.`/`..` components.
+///
+/// If canonicalization fails (for example because `path` is a broken symlink), falls back to
+/// the lexical absolute path instead of aborting.
+pub fn canonicalize_or_absolute(path: &Path) -> PathBuf {
+    path.canonicalize()
+        .or_else(|_| absolute_path(path))
+        .unwrap_or_else(|_| path.to_owned())
+}
+
+/// Returns `path`'s location relative to `base` (an absolute directory), if `path` lies under
+/// it; otherwise, falls back to `path`'s absolute form.
+///
+/// Strips the prefix lexically, against `path`'s plain absolute form, rather than against its
+/// canonicalized one: re-resolving symlinks here would collapse distinct symlinked matches
+/// (e.g. reached through two different symlinked directories) down to the same printed path.
+pub fn path_relative_from(path: &Path, base: &Path) -> PathBuf {
+    let abs_path = path_absolute_form(path).unwrap_or_else(|_| path.to_owned());
+    abs_path
+        .strip_prefix(base)
+        .map(Path::to_path_buf)
+        .unwrap_or(abs_path)
 }
 
 // Path::is_dir() is not guaranteed to be intuitively correct for "." and ".."
@@ -40,13 +94,25 @@ pub fn is_dir(path: &Path) -> bool {
 }
 
 #[cfg(any(unix, target_os = "redox"))]
-pub fn is_executable(md: &fs::Metadata) -> bool {
+pub fn is_executable(_path: &Path, md: &fs::Metadata) -> bool {
     md.permissions().mode() & 0o111 != 0
 }
 
+// Windows doesn't have a notion of an executable permission bit, so fall back to matching
+// against the set of extensions that the OS itself treats as executable (see `PATHEXT`).
 #[cfg(windows)]
-pub fn is_executable(_: &fs::Metadata) -> bool {
-    false
+pub fn is_executable(path: &Path, _: &fs::Metadata) -> bool {
+    const WINDOWS_EXECUTABLE_EXTENSIONS: &[&str] =
+        &["exe", "bat", "cmd", "com", "msi", "ps1", "vbs"];
+
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| {
+            WINDOWS_EXECUTABLE_EXTENSIONS
+                .iter()
+                .any(|e| e.eq_ignore_ascii_case(ext))
+        })
+        .unwrap_or(false)
 }
 
 pub fn is_empty(entry: &walk::DirEntry) -> bool {
@@ -87,6 +153,26 @@ pub fn is_pipe(_: &fs::FileType) -> bool {
     false
 }
 
+#[cfg(any(unix, target_os = "redox"))]
+pub fn is_char_device(ft: &fs::FileType) -> bool {
+    ft.is_char_device()
+}
+
+#[cfg(windows)]
+pub fn is_char_device(_: &fs::FileType) -> bool {
+    false
+}
+
+#[cfg(any(unix, target_os = "redox"))]
+pub fn is_block_device(ft: &fs::FileType) -> bool {
+    ft.is_block_device()
+}
+
+#[cfg(windows)]
+pub fn is_block_device(_: &fs::FileType) -> bool {
+    false
+}
+
 #[cfg(any(unix, target_os = "redox"))]
 pub fn osstr_to_bytes(input: &OsStr) -> Cow<[u8]> {
     use std::os::unix::ffi::OsStrExt;
@@ -127,7 +213,7 @@ pub fn default_path_separator() -> Option<String> {
 
 #[cfg(test)]
 mod tests {
-    use super::strip_current_dir;
+    use super::{canonicalize_or_absolute, strip_current_dir};
     use std::path::Path;
 
     #[test]
@@ -143,4 +229,32 @@ mod tests {
             Path::new("foo/bar/baz")
         );
     }
+
+    #[test]
+    #[cfg(unix)]
+    fn canonicalize_or_absolute_resolves_symlink() {
+        use std::os::unix::fs::symlink;
+
+        let temp_dir = tempdir::TempDir::new("fd-tests-canonicalize").unwrap();
+        let target = temp_dir.path().join("target.txt");
+        std::fs::write(&target, "").unwrap();
+
+        let link = temp_dir.path().join("link.txt");
+        symlink(&target, &link).unwrap();
+
+        assert_eq!(canonicalize_or_absolute(&link), target.canonicalize().unwrap());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn canonicalize_or_absolute_falls_back_on_broken_symlink() {
+        use std::os::unix::fs::symlink;
+
+        let temp_dir = tempdir::TempDir::new("fd-tests-canonicalize").unwrap();
+        let missing_target = temp_dir.path().join("does-not-exist.txt");
+        let link = temp_dir.path().join("broken-link.txt");
+        symlink(&missing_target, &link).unwrap();
+
+        assert_eq!(canonicalize_or_absolute(&link), link);
+    }
 }
@@ -5,6 +5,8 @@ pub struct FileTypes {
     pub symlinks: bool,
     pub sockets: bool,
     pub pipes: bool,
+    pub char_devices: bool,
+    pub block_devices: bool,
     pub executables_only: bool,
     pub empty_only: bool,
 }
@@ -17,8 +19,58 @@ impl Default for FileTypes {
             symlinks: false,
             sockets: false,
             pipes: false,
+            char_devices: false,
+            block_devices: false,
             executables_only: false,
             empty_only: false,
         }
     }
 }
+
+impl FileTypes {
+    /// Builds a `FileTypes` set from the values of a `--type` argument (the short or long name
+    /// of each requested type, e.g. "f", "file", "d", "directory", ...). If only 'empty' was
+    /// specified, broadens the set to search both files and directories, since 'empty' alone
+    /// isn't a type.
+    pub fn from_values<'a>(values: impl Iterator<Item = &'a str>) -> FileTypes {
+        let mut file_types = Self::from_values_unbroadened(values);
+
+        if file_types.empty_only && !(file_types.files || file_types.directories) {
+            file_types.files = true;
+            file_types.directories = true;
+        }
+
+        file_types
+    }
+
+    /// Builds a `FileTypes` set from the values of a `--type-not` argument. Unlike
+    /// `from_values`, 'empty' on its own is *not* broadened to files and directories: since the
+    /// flags here are OR'd together to decide what to exclude, broadening would turn
+    /// `--type-not empty` into "exclude every file and directory", rather than just the empty
+    /// ones.
+    pub fn from_exclude_values<'a>(values: impl Iterator<Item = &'a str>) -> FileTypes {
+        Self::from_values_unbroadened(values)
+    }
+
+    fn from_values_unbroadened<'a>(values: impl Iterator<Item = &'a str>) -> FileTypes {
+        let mut file_types = FileTypes::default();
+        for value in values {
+            match value {
+                "f" | "file" => file_types.files = true,
+                "d" | "directory" => file_types.directories = true,
+                "l" | "symlink" => file_types.symlinks = true,
+                "x" | "executable" => {
+                    file_types.executables_only = true;
+                    file_types.files = true;
+                }
+                "e" | "empty" => file_types.empty_only = true,
+                "s" | "socket" => file_types.sockets = true,
+                "p" | "pipe" => file_types.pipes = true,
+                "c" | "char-device" => file_types.char_devices = true,
+                "b" | "block-device" => file_types.block_devices = true,
+                _ => unreachable!(),
+            }
+        }
+        file_types
+    }
+}
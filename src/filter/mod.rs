@@ -3,9 +3,17 @@ pub use self::time::TimeFilter;
 
 #[cfg(unix)]
 pub use self::owner::OwnerFilter;
+#[cfg(unix)]
+pub use self::perm::PermFilter;
+#[cfg(unix)]
+pub use self::same_file::SameFileFilter;
 
 mod size;
 mod time;
 
 #[cfg(unix)]
 mod owner;
+#[cfg(unix)]
+mod perm;
+#[cfg(unix)]
+mod same_file;
@@ -0,0 +1,186 @@
+use anyhow::{anyhow, Result};
+use std::fs;
+
+/// A `--perm` mode matcher, following `find`'s `-perm` semantics.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PermFilter {
+    /// `--perm mode`: the permission bits match `mode` exactly.
+    Exact(u32),
+    /// `--perm -mode`: all of the bits in `mode` are set.
+    All(u32),
+    /// `--perm /mode`: any of the bits in `mode` are set.
+    Any(u32),
+}
+
+impl PermFilter {
+    /// Parses a `--perm` argument, which is either an octal mode (e.g. `644`) or a `chmod`-style
+    /// symbolic mode (e.g. `u+rwx,g+rx,o+r`), optionally prefixed with `-` (all bits) or `/`
+    /// (any bit). Without a prefix, the permission bits must match exactly.
+    pub fn from_string(s: &str) -> Result<Self> {
+        let (build, spec) = match s.strip_prefix('-') {
+            Some(rest) => (PermFilter::All as fn(u32) -> PermFilter, rest),
+            None => match s.strip_prefix('/') {
+                Some(rest) => (PermFilter::Any as fn(u32) -> PermFilter, rest),
+                None => (PermFilter::Exact as fn(u32) -> PermFilter, s),
+            },
+        };
+
+        if spec.is_empty() {
+            return Err(anyhow!("'{}' is not a valid mode", s));
+        }
+
+        let mode = if spec.bytes().all(|b| b.is_ascii_digit()) {
+            u32::from_str_radix(spec, 8)
+                .map_err(|_| anyhow!("'{}' is not a valid octal mode", spec))?
+        } else {
+            parse_symbolic_mode(spec)?
+        };
+
+        Ok(build(mode))
+    }
+
+    pub fn is_match(&self, mode: u32) -> bool {
+        let mode = mode & 0o7777;
+        match *self {
+            PermFilter::Exact(m) => mode == m,
+            PermFilter::All(m) => mode & m == m,
+            PermFilter::Any(m) => mode & m != 0,
+        }
+    }
+
+    #[cfg(unix)]
+    pub fn matches(&self, md: &fs::Metadata) -> bool {
+        use std::os::unix::fs::MetadataExt;
+
+        self.is_match(md.mode())
+    }
+}
+
+/// Parses a comma-separated list of `chmod`-style clauses (`[ugoa]*[+-=][rwx]*`) into the mask
+/// of permission bits they refer to.
+fn parse_symbolic_mode(spec: &str) -> Result<u32> {
+    let mut mode = 0;
+
+    for clause in spec.split(',') {
+        let mut chars = clause.chars().peekable();
+
+        let mut who_mask = 0;
+        while let Some(&c) = chars.peek() {
+            who_mask |= match c {
+                'u' => 0o700,
+                'g' => 0o070,
+                'o' => 0o007,
+                'a' => 0o777,
+                _ => break,
+            };
+            chars.next();
+        }
+        if who_mask == 0 {
+            who_mask = 0o777;
+        }
+
+        let op = match chars.next() {
+            Some(op @ '+') | Some(op @ '-') | Some(op @ '=') => op,
+            _ => return Err(anyhow!("'{}' is not a valid symbolic mode clause", clause)),
+        };
+
+        let mut perm_mask = 0;
+        for c in chars {
+            perm_mask |= match c {
+                'r' => 0o444,
+                'w' => 0o222,
+                'x' => 0o111,
+                _ => {
+                    return Err(anyhow!(
+                        "'{}' is not a valid permission character in '{}'",
+                        c,
+                        clause
+                    ))
+                }
+            };
+        }
+
+        // Clauses apply against a base mode of `000`, like `find`'s `-perm` symbolic mode: `+`
+        // adds bits, `-` removes them (a no-op unless an earlier clause already set them), and
+        // `=` sets the named class's bits exactly, clearing any it previously held.
+        match op {
+            '+' => mode |= who_mask & perm_mask,
+            '-' => mode &= !(who_mask & perm_mask),
+            '=' => mode = (mode & !who_mask) | (who_mask & perm_mask),
+            _ => unreachable!(),
+        }
+    }
+
+    Ok(mode)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PermFilter::*;
+    use super::*;
+
+    macro_rules! perm_tests {
+        ($($name:ident: $value:expr => $result:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    assert_eq!(PermFilter::from_string($value).unwrap(), $result);
+                }
+            )*
+        };
+    }
+
+    perm_tests! {
+        exact_octal: "644" => Exact(0o644),
+        exact_octal_with_special_bits: "4755" => Exact(0o4755),
+        all_octal: "-644" => All(0o644),
+        any_octal: "/644" => Any(0o644),
+        any_symbolic: "/o+w" => Any(0o002),
+        all_symbolic: "-u+rwx" => All(0o700),
+        exact_symbolic_multiple_clauses: "u+rwx,g+rx,o+r" => Exact(0o754),
+        symbolic_all_classes: "a+rwx" => Exact(0o777),
+        symbolic_default_who: "+w" => Exact(0o222),
+        symbolic_minus_adds_no_bits: "u-rwx" => Exact(0o000),
+        symbolic_equals_sets_only_named_class: "u=rwx,g-rx" => Exact(0o700),
+    }
+
+    macro_rules! perm_failures {
+        ($($name:ident: $value:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    assert!(PermFilter::from_string($value).is_err());
+                }
+            )*
+        };
+    }
+
+    perm_failures! {
+        empty: "",
+        only_prefix: "-",
+        invalid_octal: "999",
+        invalid_symbolic_char: "u+z",
+        missing_operator: "uw",
+    }
+
+    #[test]
+    fn is_match_exact() {
+        let f = PermFilter::from_string("644").unwrap();
+        assert!(f.is_match(0o644));
+        assert!(!f.is_match(0o600));
+    }
+
+    #[test]
+    fn is_match_all() {
+        let f = PermFilter::from_string("-600").unwrap();
+        assert!(f.is_match(0o644));
+        assert!(!f.is_match(0o044));
+    }
+
+    #[test]
+    fn is_match_any() {
+        let f = PermFilter::from_string("/o+w").unwrap();
+        assert!(f.is_match(0o646));
+        assert!(!f.is_match(0o644));
+    }
+}
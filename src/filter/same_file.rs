@@ -0,0 +1,30 @@
+use std::fs;
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+/// Matches entries that share the same device and inode as a reference file, i.e. hardlinks of
+/// that file (`find -samefile`).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SameFileFilter {
+    dev: u64,
+    ino: u64,
+}
+
+impl SameFileFilter {
+    /// Stats `path` and builds a filter matching its hardlinks. Errors if `path` doesn't exist.
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let metadata = fs::metadata(path)
+            .with_context(|| format!("'{}' is not a valid path", path.display()))?;
+
+        Ok(SameFileFilter {
+            dev: metadata.dev(),
+            ino: metadata.ino(),
+        })
+    }
+
+    pub fn matches(&self, md: &fs::Metadata) -> bool {
+        md.dev() == self.dev && md.ino() == self.ino
+    }
+}
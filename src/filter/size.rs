@@ -186,6 +186,21 @@ mod tests {
         ensure_bb_format_returns_none: "+1bb",
     }
 
+    // Bare sizes (no '+' or '-' sign) require an exact match.
+    gen_size_filter_parse_test! {
+        bare_byte:  ("500b", SizeFilter::Equals(500)),
+        bare_kilo:  ("10k",  SizeFilter::Equals(10000)),
+        bare_kibi:  ("10ki", SizeFilter::Equals(10240)),
+    }
+
+    #[test]
+    fn is_within_equals() {
+        let f = SizeFilter::from_string("500b").unwrap();
+        assert!(f.is_within(500));
+        assert!(!f.is_within(499));
+        assert!(!f.is_within(501));
+    }
+
     #[test]
     fn is_within_less_than() {
         let f = SizeFilter::from_string("-1k").unwrap();
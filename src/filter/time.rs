@@ -77,4 +77,54 @@ mod tests {
             .unwrap()
             .applies_to(&t1m_ago));
     }
+
+    #[test]
+    fn parses_calendar_units() {
+        let ref_time = humantime::parse_rfc3339("2020-01-01T00:00:00Z").unwrap();
+
+        let two_months_ago = ref_time - Duration::from_secs(2 * 2_630_016);
+        assert_eq!(
+            TimeFilter::after(&ref_time, "2months"),
+            Some(TimeFilter::After(two_months_ago))
+        );
+
+        let one_year_ago = ref_time - Duration::from_secs(31_557_600);
+        assert_eq!(
+            TimeFilter::after(&ref_time, "1year"),
+            Some(TimeFilter::After(one_year_ago))
+        );
+    }
+
+    #[test]
+    fn parses_mixed_units() {
+        let ref_time = humantime::parse_rfc3339("2020-01-01T00:00:00Z").unwrap();
+
+        let expected = ref_time - Duration::from_secs(36 * 3600);
+        assert_eq!(
+            TimeFilter::after(&ref_time, "1d12h"),
+            Some(TimeFilter::After(expected))
+        );
+    }
+
+    #[test]
+    fn rejects_unparsable_duration() {
+        let ref_time = humantime::parse_rfc3339("2020-01-01T00:00:00Z").unwrap();
+
+        assert_eq!(TimeFilter::after(&ref_time, "3bogus"), None);
+    }
+
+    #[test]
+    fn is_time_filter_applicable_for_date_without_time() {
+        let ref_time = humantime::parse_rfc3339("2017-10-05T00:00:00Z").unwrap();
+        let limit = humantime::parse_rfc3339("2017-10-01T00:00:00Z").unwrap();
+        let before_limit = limit - Duration::from_secs(1);
+
+        // A bare date (no time-of-day) is interpreted as midnight.
+        assert!(TimeFilter::before(&ref_time, "2017-10-01")
+            .unwrap()
+            .applies_to(&before_limit));
+        assert!(TimeFilter::after(&ref_time, "2017-10-01")
+            .unwrap()
+            .applies_to(&limit));
+    }
 }
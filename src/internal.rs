@@ -0,0 +1,155 @@
+use anyhow::{Context, Result};
+
+/// Parse the argument to `--threads`.
+///
+/// A value of `0` means "automatically detect the number of available CPUs", the same default
+/// that is used when `--threads` is not given at all. Anything else that doesn't parse as a
+/// positive integer is rejected.
+pub fn num_threads(raw: &str) -> Result<usize> {
+    let n: usize = raw
+        .parse()
+        .with_context(|| format!("Invalid number of threads: '{}'", raw))?;
+
+    Ok(if n == 0 { available_parallelism() } else { n })
+}
+
+/// Returns the effective number of CPUs available to this process, used as the default
+/// `--threads` value.
+///
+/// On Linux, this consults the cgroup CPU quota so that a container limited to, say, 2 CPUs
+/// doesn't over-thread against the host's full core count, the way `num_cpus::get()` would.
+/// Falls back to `num_cpus::get()` whenever no quota is in effect, or the cgroup files can't be
+/// read or parsed.
+pub fn available_parallelism() -> usize {
+    #[cfg(target_os = "linux")]
+    if let Some(n) = linux::cgroup_cpu_quota() {
+        return n;
+    }
+
+    num_cpus::get()
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::fs;
+
+    /// Derives an effective CPU count from the process's cgroup CPU quota, consulting cgroup v2's
+    /// `cpu.max` first and falling back to cgroup v1's `cpu.cfs_quota_us`/`cpu.cfs_period_us`.
+    /// Returns `None` if no quota is in effect (an unlimited cgroup, or no cgroup support at
+    /// all), in which case the caller should fall back to the host's full core count.
+    pub(super) fn cgroup_cpu_quota() -> Option<usize> {
+        if let Ok(cpu_max) = fs::read_to_string("/sys/fs/cgroup/cpu.max") {
+            let mut fields = cpu_max.trim().split_whitespace();
+            let quota = fields.next()?;
+            let period = fields.next()?;
+            if quota == "max" {
+                return None;
+            }
+            return quota_to_cpu_count(quota, period);
+        }
+
+        let quota = fs::read_to_string("/sys/fs/cgroup/cpu/cpu.cfs_quota_us").ok()?;
+        let period = fs::read_to_string("/sys/fs/cgroup/cpu/cpu.cfs_period_us").ok()?;
+        quota_to_cpu_count(&quota, &period)
+    }
+
+    /// Converts a cgroup quota/period pair (in microseconds) into a whole number of CPUs,
+    /// rounded down and clamped to at least `1`. A non-positive quota (cgroup v1's `-1` means
+    /// "unlimited") yields `None`.
+    fn quota_to_cpu_count(quota: &str, period: &str) -> Option<usize> {
+        let quota: i64 = quota.trim().parse().ok()?;
+        let period: i64 = period.trim().parse().ok()?;
+
+        if quota <= 0 || period <= 0 {
+            return None;
+        }
+
+        Some(((quota as f64 / period as f64).floor() as usize).max(1))
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn quota_to_cpu_count_rounds_down_to_whole_cpus() {
+            assert_eq!(quota_to_cpu_count("200000", "100000"), Some(2));
+            assert_eq!(quota_to_cpu_count("250000", "100000"), Some(2));
+        }
+
+        #[test]
+        fn quota_to_cpu_count_clamps_fractional_quotas_to_one() {
+            assert_eq!(quota_to_cpu_count("50000", "100000"), Some(1));
+        }
+
+        #[test]
+        fn quota_to_cpu_count_treats_unlimited_as_none() {
+            assert_eq!(quota_to_cpu_count("-1", "100000"), None);
+        }
+
+        #[test]
+        fn quota_to_cpu_count_rejects_garbage() {
+            assert_eq!(quota_to_cpu_count("not-a-number", "100000"), None);
+        }
+    }
+}
+
+/// Parse the argument to `--threads-ratio`, and apply it to the given number of available CPUs.
+///
+/// The result is clamped to a minimum of `1`, so that e.g. a ratio of `0.1` on a 4-core machine
+/// never ends up disabling the thread pool entirely.
+pub fn num_threads_from_ratio(raw: &str, num_cpus: usize) -> Result<usize> {
+    let ratio: f64 = raw
+        .parse()
+        .with_context(|| format!("Invalid thread ratio: '{}'", raw))?;
+
+    if !ratio.is_finite() || ratio <= 0.0 {
+        return Err(anyhow::anyhow!(
+            "Invalid thread ratio: '{}' (must be a positive number)",
+            raw
+        ));
+    }
+
+    Ok(((num_cpus as f64) * ratio).round().max(1.0) as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn num_threads_zero_means_auto() {
+        assert_eq!(num_threads("0").unwrap(), available_parallelism());
+    }
+
+    #[test]
+    fn num_threads_explicit_value() {
+        assert_eq!(num_threads("4").unwrap(), 4);
+    }
+
+    #[test]
+    fn num_threads_rejects_garbage() {
+        assert!(num_threads("not-a-number").is_err());
+        assert!(num_threads("-1").is_err());
+    }
+
+    #[test]
+    fn num_threads_from_ratio_scales_cpu_count() {
+        assert_eq!(num_threads_from_ratio("0.5", 8).unwrap(), 4);
+        assert_eq!(num_threads_from_ratio("2", 4).unwrap(), 8);
+        assert_eq!(num_threads_from_ratio("1", 4).unwrap(), 4);
+    }
+
+    #[test]
+    fn num_threads_from_ratio_clamps_to_at_least_one() {
+        assert_eq!(num_threads_from_ratio("0.1", 4).unwrap(), 1);
+        assert_eq!(num_threads_from_ratio("0.01", 1).unwrap(), 1);
+    }
+
+    #[test]
+    fn num_threads_from_ratio_rejects_garbage() {
+        assert!(num_threads_from_ratio("not-a-number", 4).is_err());
+        assert!(num_threads_from_ratio("0", 4).is_err());
+        assert!(num_threads_from_ratio("-0.5", 4).is_err());
+    }
+}
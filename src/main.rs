@@ -1,16 +1,21 @@
 mod app;
+mod config;
 mod error;
 mod exec;
 mod exit_codes;
 mod filesystem;
 mod filetypes;
 mod filter;
+mod internal;
 mod options;
 mod output;
 mod regex_helper;
 mod walk;
 
 use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, Read};
 use std::path::{Path, PathBuf};
 use std::process;
 use std::sync::Arc;
@@ -27,9 +32,11 @@ use crate::exec::CommandTemplate;
 use crate::exit_codes::ExitCode;
 use crate::filetypes::FileTypes;
 #[cfg(unix)]
-use crate::filter::OwnerFilter;
+use crate::filter::{OwnerFilter, PermFilter, SameFileFilter};
 use crate::filter::{SizeFilter, TimeFilter};
 use crate::options::Options;
+use crate::output::OutputFormat;
+use crate::walk::{FollowMode, SortBy};
 use crate::regex_helper::{pattern_has_uppercase_char, pattern_matches_strings_with_leading_dot};
 
 // We use jemalloc for performance reasons, see https://github.com/sharkdp/fd/pull/481
@@ -49,8 +56,30 @@ ow=0:or=0;38;5;16;48;5;203:no=0:ex=1;38;5;203:cd=0;38;5;203;48;5;236:mi=0;38;5;1
 38;5;185:*.jpg=0;38;5;208:*.mir=0;38;5;48:*.sxi=0;38;5;186:*.bz2=4;38;5;203:*.odt=0;38;5;186:*.mov=0;38;5;208:*.toc=0;38;5;243:*.bat=1;38;5;203:*.asa=0;38;5;48:*.awk=0;38;5;48:*.sbt=0;38;5;48:*.vcd=4;38;5;203:*.kts=0;38;5;48:*.arj=4;38;5;203:*.blg=0;38;5;243:*.c++=0;38;5;48:*.odp=0;38;5;186:*.bbl=0;38;5;243:*.idx=0;38;5;243:*.com=1;38;5;203:*.mp3=0;38;5;208:*.avi=0;38;5;208:*.def=0;38;5;48:*.cgi=0;38;5;48:*.zip=4;38;5;203:*.ttf=0;38;5;208:*.ppt=0;38;5;186:*.tml=0;38;5;149:*.fsx=0;38;5;48:*.h++=0;38;5;48:*.rtf=0;38;5;186:*.inl=0;38;5;48:*.yaml=0;38;5;149:*.html=0;38;5;185:*.mpeg=0;38;5;208:*.java=0;38;5;48:*.hgrc=0;38;5;149:*.orig=0;38;5;243:*.conf=0;38;5;149:*.dart=0;38;5;48:*.psm1=0;38;5;48:*.rlib=0;38;5;243:*.fish=0;38;5;48:*.bash=0;38;5;48:*.make=0;38;5;149:*.docx=0;38;5;186:*.json=0;38;5;149:*.psd1=0;38;5;48:*.lisp=0;38;5;48:*.tbz2=4;38;5;203:*.diff=0;38;5;48:*.epub=0;38;5;186:*.xlsx=0;38;5;186:*.pptx=0;38;5;186:*.toml=0;38;5;149:*.h264=0;38;5;208:*.purs=0;38;5;48:*.flac=0;38;5;208:*.tiff=0;38;5;208:*.jpeg=0;38;5;208:*.lock=0;38;5;243:*.less=0;38;5;48:*.dyn_o=0;38;5;243:*.scala=0;38;5;48:*.mdown=0;38;5;185:*.shtml=0;38;5;185:*.class=0;38;5;243:*.cache=0;38;5;243:*.cmake=0;38;5;149:*passwd=0;38;5;149:*.swift=0;38;5;48:*shadow=0;38;5;149:*.xhtml=0;38;5;185:*.patch=0;38;5;48:*.cabal=0;38;5;48:*README=0;38;5;16;48;5;186:*.toast=4;38;5;203:*.ipynb=0;38;5;48:*COPYING=0;38;5;249:*.gradle=0;38;5;48:*.matlab=0;38;5;48:*.config=0;38;5;149:*LICENSE=0;38;5;249:*.dyn_hi=0;38;5;243:*.flake8=0;38;5;149:*.groovy=0;38;5;48:*INSTALL=0;38;5;16;48;5;186:*TODO.md=1:*.ignore=0;38;5;149:*Doxyfile=0;38;5;149:*TODO.txt=1:*setup.py=0;38;5;149:*Makefile=0;38;5;149:*.gemspec=0;38;5;149:*.desktop=0;38;5;149:*.rgignore=0;38;5;149:*.markdown=0;38;5;185:*COPYRIGHT=0;38;5;249:*configure=0;38;5;149:*.DS_Store=0;38;5;243:*.kdevelop=0;38;5;149:*.fdignore=0;38;5;149:*README.md=0;38;5;16;48;5;186:*.cmake.in=0;38;5;149:*SConscript=0;38;5;149:*CODEOWNERS=0;38;5;149:*.localized=0;38;5;243:*.gitignore=0;38;5;149:*Dockerfile=0;38;5;149:*.gitconfig=0;38;5;149:*INSTALL.md=0;38;5;16;48;5;186:*README.txt=0;38;5;16;48;5;186:*SConstruct=0;38;5;149:*.scons_opt=0;38;5;243:*.travis.yml=0;38;5;186:*.gitmodules=0;38;5;149:*.synctex.gz=0;38;5;243:*LICENSE-MIT=0;38;5;249:*MANIFEST.in=0;38;5;149:*Makefile.in=0;38;5;243:*Makefile.am=0;38;5;149:*INSTALL.txt=0;38;5;16;48;5;186:*configure.ac=0;38;5;149:*.applescript=0;38;5;48:*appveyor.yml=0;38;5;186:*.fdb_latexmk=0;38;5;243:*CONTRIBUTORS=0;38;5;16;48;5;186:*.clang-format=0;38;5;149:*LICENSE-APACHE=0;38;5;249:*CMakeLists.txt=0;38;5;149:*CMakeCache.txt=0;38;5;243:*.gitattributes=0;38;5;149:*CONTRIBUTORS.md=0;38;5;16;48;5;186:*.sconsign.dblite=0;38;5;243:*requirements.txt=0;38;5;149:*CONTRIBUTORS.txt=0;38;5;16;48;5;186:*package-lock.json=0;38;5;243:*.CFUserTextEncoding=0;38;5;243
 ";
 
+/// Prepends default flags from the config file and the `FD_DEFAULT_OPTS` environment variable to
+/// the process's own command-line arguments, so that explicit flags (which come later) override
+/// them. Precedence, from lowest to highest: config file, `FD_DEFAULT_OPTS`, command line.
+fn args_with_default_opts() -> Result<Vec<OsString>> {
+    let mut args: Vec<OsString> = env::args_os().collect();
+
+    let mut defaults = config::args_from_config_file()?;
+
+    if let Some(default_opts) = env::var_os("FD_DEFAULT_OPTS") {
+        let default_opts = default_opts.to_string_lossy();
+        let default_opts = shell_words::split(&default_opts)
+            .map_err(|e| anyhow!("Failed to parse FD_DEFAULT_OPTS: {}", e))?;
+        defaults.extend(default_opts.into_iter().map(OsString::from));
+    }
+
+    // Keep argv[0] (the program name) in place and splice the defaults in right after it, so
+    // that the user's own arguments still come last and win any conflicting flag.
+    args.splice(1..1, defaults);
+
+    Ok(args)
+}
+
 fn run() -> Result<ExitCode> {
-    let matches = app::build_app().get_matches_from(env::args_os());
+    let matches = app::build_app().get_matches_from(args_with_default_opts()?);
 
     // Set the current working directory of the process
     if let Some(base_directory) = matches.value_of_os("base-directory") {
@@ -76,20 +105,51 @@ fn run() -> Result<ExitCode> {
         ));
     }
 
-    // Get the search pattern
-    let pattern = matches
-        .value_of_os("pattern")
-        .map(|p| {
-            p.to_str()
-                .ok_or_else(|| anyhow!("The search pattern includes invalid UTF-8 sequences."))
-        })
-        .transpose()?
-        .unwrap_or("");
+    // Get the search pattern, either from the positional argument or from a file (or stdin, if
+    // the file path is '-'), stripping a trailing newline in the latter case.
+    let pattern = if let Some(pattern_file) = matches.value_of_os("pattern-file") {
+        let content = if pattern_file == "-" {
+            let mut buffer = String::new();
+            io::stdin()
+                .read_to_string(&mut buffer)
+                .context("Could not read pattern from stdin")?;
+            buffer
+        } else {
+            fs::read_to_string(pattern_file).with_context(|| {
+                format!(
+                    "Could not read pattern from '{}'",
+                    Path::new(pattern_file).to_string_lossy()
+                )
+            })?
+        };
 
-    // Get one or more root directories to search.
-    let passed_arguments = matches
-        .values_of_os("path")
-        .or_else(|| matches.values_of_os("search-path"));
+        content.trim_end_matches(&['\r', '\n'][..]).to_owned()
+    } else {
+        matches
+            .value_of_os("pattern")
+            .map(|p| {
+                p.to_str()
+                    .ok_or_else(|| anyhow!("The search pattern includes invalid UTF-8 sequences."))
+            })
+            .transpose()?
+            .unwrap_or("")
+            .to_owned()
+    };
+    let pattern = pattern.as_str();
+
+    // Get one or more root directories to search, from the positional <path> argument(s) and/or
+    // the repeatable '--search-path', merging both when both are given.
+    let passed_arguments: Option<Vec<&std::ffi::OsStr>> = match (
+        matches.values_of_os("path"),
+        matches.values_of_os("search-path"),
+    ) {
+        (Some(paths), Some(search_paths)) => Some(paths.chain(search_paths).collect()),
+        (Some(paths), None) => Some(paths.collect()),
+        (None, Some(search_paths)) => Some(search_paths.collect()),
+        (None, None) => None,
+    };
+
+    let quiet = matches.is_present("quiet");
 
     let mut search_paths = if let Some(paths) = passed_arguments {
         let mut directories = vec![];
@@ -97,7 +157,7 @@ fn run() -> Result<ExitCode> {
             let path_buffer = PathBuf::from(path);
             if filesystem::is_dir(&path_buffer) {
                 directories.push(path_buffer);
-            } else {
+            } else if !quiet {
                 print_error(format!(
                     "Search path '{}' is not a directory.",
                     path_buffer.to_string_lossy()
@@ -112,9 +172,18 @@ fn run() -> Result<ExitCode> {
 
     // Check if we have no valid search paths.
     if search_paths.is_empty() {
+        if quiet {
+            return Ok(ExitCode::GeneralError);
+        }
         return Err(anyhow!("No valid search paths given."));
     }
 
+    // Whether every root we're searching from is (explicitly or implicitly) the current
+    // directory. This is the only case in which results can carry a leading './' component.
+    let roots_are_cwd = search_paths
+        .iter()
+        .all(|path_buffer| path_buffer.as_os_str() == current_directory.as_os_str());
+
     if matches.is_present("absolute-path") {
         search_paths = search_paths
             .iter()
@@ -144,21 +213,52 @@ fn run() -> Result<ExitCode> {
         ));
     }
 
-    let pattern_regex = if matches.is_present("glob") && !pattern.is_empty() {
-        let glob = GlobBuilder::new(pattern).literal_separator(true).build()?;
-        glob.regex().to_owned()
-    } else if matches.is_present("fixed-strings") {
-        // Treat pattern as literal string if '--fixed-strings' is used
-        regex::escape(pattern)
-    } else {
-        String::from(pattern)
+    let literal_separator = !matches.is_present("no-literal-separator");
+
+    // Applies '--glob'/'--fixed-strings' the same way to the primary pattern and to each
+    // '--and' pattern, so that they're all interpreted consistently.
+    let transform_pattern = |pattern: &str| -> Result<String> {
+        Ok(if matches.is_present("glob") && !pattern.is_empty() {
+            let glob = GlobBuilder::new(pattern)
+                .literal_separator(literal_separator)
+                .build()?;
+            glob.regex().to_owned()
+        } else if matches.is_present("fixed-strings") {
+            // Treat pattern as literal string if '--fixed-strings' is used
+            regex::escape(pattern)
+        } else {
+            String::from(pattern)
+        })
     };
 
+    let pattern_regex = transform_pattern(pattern)?;
+
     // The search will be case-sensitive if the command line flag is set or
     // if the pattern has an uppercase character (smart case).
     let case_sensitive = !matches.is_present("ignore-case")
         && (matches.is_present("case-sensitive") || pattern_has_uppercase_char(&pattern_regex));
 
+    // Builds additional patterns (for '--and'/'--not') the same way as the primary pattern,
+    // respecting '--glob'/'--fixed-strings' and mirroring its case sensitivity.
+    let build_extra_patterns = |arg_name: &str| -> Result<Vec<_>> {
+        matches
+            .values_of(arg_name)
+            .into_iter()
+            .flatten()
+            .map(|pattern| {
+                let pattern = transform_pattern(pattern)?;
+                RegexBuilder::new(&pattern)
+                    .case_insensitive(!case_sensitive)
+                    .dot_matches_new_line(true)
+                    .build()
+                    .map_err(|e| anyhow!("{}", e))
+            })
+            .collect::<Result<Vec<_>>>()
+    };
+
+    let and_patterns = build_extra_patterns("and")?;
+    let not_patterns = build_extra_patterns("not")?;
+
     #[cfg(windows)]
     let ansi_colors_support =
         ansi_term::enable_ansi_support().is_ok() || std::env::var_os("TERM").is_some();
@@ -166,11 +266,24 @@ fn run() -> Result<ExitCode> {
     #[cfg(not(windows))]
     let ansi_colors_support = true;
 
+    // 'force' is an alias for 'always', for compatibility with tools that use that name instead.
+    let color_when = matches
+        .value_of("color")
+        .map(|c| if c == "force" { "always" } else { c });
+
     let interactive_terminal = atty::is(Stream::Stdout);
-    let colored_output = match matches.value_of("color") {
+    // 'FORCE_COLOR' lets CI systems and other non-interactive consumers that still render ANSI
+    // opt into color without a TTY, the positive-case complement to 'NO_COLOR'. A value of "0"
+    // is treated the same as the variable being unset.
+    let force_color = env::var_os("FORCE_COLOR").map_or(false, |v| v != "0");
+    let colored_output = match color_when {
         Some("always") => true,
         Some("never") => false,
-        _ => ansi_colors_support && env::var_os("NO_COLOR").is_none() && interactive_terminal,
+        _ => {
+            ansi_colors_support
+                && env::var_os("NO_COLOR").is_none()
+                && (interactive_terminal || force_color)
+        }
     };
 
     let path_separator = matches
@@ -178,17 +291,60 @@ fn run() -> Result<ExitCode> {
         .map_or_else(filesystem::default_path_separator, |s| Some(s.to_owned()));
 
     let ls_colors = if colored_output {
-        Some(LsColors::from_env().unwrap_or_else(|| LsColors::from_string(DEFAULT_LS_COLORS)))
+        // Layer fd's built-in theme underneath any user-supplied 'LS_COLORS', so that indicators
+        // the user didn't customize (e.g. a missing 'ex=' entry) still get a sensible default
+        // instead of no color at all. Entries from 'user_ls_colors' are parsed last and therefore
+        // take precedence for any indicator they do specify.
+        Some(match env::var("LS_COLORS") {
+            Ok(user_ls_colors) => {
+                LsColors::from_string(&format!("{}:{}", DEFAULT_LS_COLORS, user_ls_colors))
+            }
+            Err(_) => LsColors::from_string(DEFAULT_LS_COLORS),
+        })
     } else {
         None
     };
 
+    let exec_dry_run = matches.is_present("exec-dry-run");
+    let exec_cwd = matches.is_present("exec-cwd");
+    let exec_timeout = match matches.value_of("exec-timeout") {
+        Some(t) => Some(
+            humantime::parse_duration(t)
+                .map_err(|_| anyhow!("'{}' is not a valid duration. See 'fd --help'.", t))?,
+        ),
+        None => None,
+    };
+
     let command = if let Some(args) = matches.values_of("exec") {
-        Some(CommandTemplate::new(args, path_separator.clone()))
+        Some(
+            CommandTemplate::new(args, path_separator.clone())
+                .dry_run(exec_dry_run)
+                .exec_cwd(exec_cwd)
+                .timeout(exec_timeout),
+        )
     } else if let Some(args) = matches.values_of("exec-batch") {
-        Some(CommandTemplate::new_batch(args, path_separator.clone())?)
+        if exec_cwd {
+            return Err(anyhow!(
+                "'--exec-cwd' can only be used together with '--exec'."
+            ));
+        }
+        Some(
+            CommandTemplate::new_batch(args, path_separator.clone())?
+                .dry_run(exec_dry_run)
+                .timeout(exec_timeout),
+        )
+    } else if exec_dry_run {
+        return Err(anyhow!(
+            "'--exec-dry-run' can only be used together with '--exec' or '--exec-batch'."
+        ));
+    } else if exec_cwd {
+        return Err(anyhow!("'--exec-cwd' can only be used together with '--exec'."));
+    } else if exec_timeout.is_some() {
+        return Err(anyhow!(
+            "'--exec-timeout' can only be used together with '--exec' or '--exec-batch'."
+        ));
     } else if matches.is_present("list-details") {
-        let color = matches.value_of("color").unwrap_or("auto");
+        let color = color_when.unwrap_or("auto");
         let color_arg = ["--color=", color].concat();
 
         #[allow(unused)]
@@ -303,6 +459,18 @@ fn run() -> Result<ExitCode> {
             ));
         }
     }
+    if let Some(path) = matches.value_of("newer-than") {
+        let reference_time = fs::metadata(path)
+            .and_then(|m| m.modified())
+            .with_context(|| format!("'{}' is not a valid path", path))?;
+        time_constraints.push(TimeFilter::After(reference_time));
+    }
+    if let Some(path) = matches.value_of("older-than") {
+        let reference_time = fs::metadata(path)
+            .and_then(|m| m.modified())
+            .with_context(|| format!("'{}' is not a valid path", path))?;
+        time_constraints.push(TimeFilter::Before(reference_time));
+    }
 
     #[cfg(unix)]
     let owner_constraint = if let Some(s) = matches.value_of("owner") {
@@ -311,112 +479,149 @@ fn run() -> Result<ExitCode> {
         None
     };
 
+    #[cfg(unix)]
+    let permission_constraint = matches
+        .value_of("perm")
+        .map(PermFilter::from_string)
+        .transpose()?;
+
+    #[cfg(unix)]
+    let same_file_filter = matches
+        .value_of("same-file-as")
+        .map(|s| SameFileFilter::from_path(Path::new(s)))
+        .transpose()?;
+
     let config = Options {
         case_sensitive,
         search_full_path: matches.is_present("full-path"),
+        search_full_path_or_name: matches.is_present("full-path-or-name"),
+        match_symlink_target: matches.is_present("follow-and-match-target"),
+        count: matches.is_present("count"),
         ignore_hidden: !(matches.is_present("hidden")
             || matches.occurrences_of("rg-alias-hidden-ignore") >= 2),
         read_fdignore: !(matches.is_present("no-ignore")
-            || matches.is_present("rg-alias-hidden-ignore")),
+            || matches.is_present("rg-alias-hidden-ignore")
+            || matches.is_present("no-ignore-dot")),
         read_vcsignore: !(matches.is_present("no-ignore")
             || matches.is_present("rg-alias-hidden-ignore")
             || matches.is_present("no-ignore-vcs")),
+        read_global_vcsignore: !(matches.is_present("no-ignore")
+            || matches.is_present("rg-alias-hidden-ignore")
+            || matches.is_present("no-ignore-vcs")
+            || matches.is_present("no-global-ignore-vcs")),
         read_global_ignore: !(matches.is_present("no-ignore")
             || matches.is_present("rg-alias-hidden-ignore")
             || matches.is_present("no-global-ignore-file")),
-        follow_links: matches.is_present("follow"),
+        read_parent_ignore: !(matches.is_present("no-ignore")
+            || matches.is_present("rg-alias-hidden-ignore")
+            || matches.is_present("no-ignore-parent")),
+        follow: if matches.is_present("follow") {
+            FollowMode::All
+        } else if matches.is_present("no-follow-roots") {
+            FollowMode::None
+        } else {
+            FollowMode::RootsOnly
+        },
+        resolve_root_symlink: matches.is_present("resolve-root-symlink"),
         one_file_system: matches.is_present("one-file-system"),
         null_separator: matches.is_present("null_separator"),
-        max_depth: matches
-            .value_of("max-depth")
-            .or_else(|| matches.value_of("rg-depth"))
-            .or_else(|| matches.value_of("exact-depth"))
-            .map(|n| usize::from_str_radix(n, 10))
-            .transpose()
-            .context("Failed to parse argument to --max-depth/--exact-depth")?,
+        max_depth: if matches.is_present("flat") {
+            Some(1)
+        } else {
+            matches
+                .value_of("max-depth")
+                .or_else(|| matches.value_of("rg-depth"))
+                .or_else(|| matches.value_of("exact-depth"))
+                .map(|n| usize::from_str_radix(n, 10))
+                .transpose()
+                .context("Failed to parse argument to --max-depth/--exact-depth")?
+        },
         min_depth: matches
             .value_of("min-depth")
             .or_else(|| matches.value_of("exact-depth"))
             .map(|n| usize::from_str_radix(n, 10))
             .transpose()
             .context("Failed to parse argument to --min-depth/--exact-depth")?,
+        depth_from_cwd: matches.value_of("depth-from") == Some("cwd"),
         prune: matches.is_present("prune"),
-        threads: std::cmp::max(
-            matches
-                .value_of("threads")
-                .map(|n| usize::from_str_radix(n, 10))
-                .transpose()
-                .context("Failed to parse number of threads")?
-                .map(|n| {
-                    if n > 0 {
-                        Ok(n)
-                    } else {
-                        Err(anyhow!("Number of threads must be positive."))
-                    }
-                })
-                .transpose()?
-                .unwrap_or_else(num_cpus::get),
-            1,
-        ),
+        threads: if let Some(num_threads) = matches.value_of("threads") {
+            internal::num_threads(num_threads)?
+        } else if let Some(ratio) = matches.value_of("threads-ratio") {
+            internal::num_threads_from_ratio(ratio, internal::available_parallelism())?
+        } else {
+            internal::available_parallelism()
+        },
         max_buffer_time: matches
             .value_of("max-buffer-time")
             .map(|n| u64::from_str_radix(n, 10))
             .transpose()
             .context("Failed to parse max. buffer time argument")?
             .map(time::Duration::from_millis),
+        no_buffer: matches.is_present("no-buffer"),
         ls_colors,
         interactive_terminal,
-        file_types: matches.values_of("file-type").map(|values| {
-            let mut file_types = FileTypes::default();
-            for value in values {
-                match value {
-                    "f" | "file" => file_types.files = true,
-                    "d" | "directory" => file_types.directories = true,
-                    "l" | "symlink" => file_types.symlinks = true,
-                    "x" | "executable" => {
-                        file_types.executables_only = true;
-                        file_types.files = true;
-                    }
-                    "e" | "empty" => file_types.empty_only = true,
-                    "s" | "socket" => file_types.sockets = true,
-                    "p" | "pipe" => file_types.pipes = true,
-                    _ => unreachable!(),
-                }
-            }
-
-            // If only 'empty' was specified, search for both files and directories:
-            if file_types.empty_only && !(file_types.files || file_types.directories) {
-                file_types.files = true;
-                file_types.directories = true;
-            }
-
-            file_types
-        }),
+        file_types: matches
+            .values_of("file-type")
+            .map(FileTypes::from_values),
+        exclude_file_types: matches
+            .values_of("type-not")
+            .map(FileTypes::from_exclude_values),
         extensions: matches
             .values_of("extension")
             .map(|exts| {
-                let patterns = exts
-                    .map(|e| e.trim_start_matches('.'))
-                    .map(|e| format!(r".\.{}$", regex::escape(e)));
+                // In 'any' mode, a compound extension like 'tar.gz' matches any of its
+                // dot-separated suffix components ('tar' or 'gz'), not just the last one.
+                let match_any_suffix_component = matches.value_of("extension-mode") == Some("any");
+                let patterns = exts.filter(|e| !e.is_empty()).map(|e| {
+                    let e = regex::escape(e.trim_start_matches('.'));
+                    if match_any_suffix_component {
+                        format!(r".\.{}(\.|$)", e)
+                    } else {
+                        format!(r".\.{}$", e)
+                    }
+                });
                 RegexSetBuilder::new(patterns)
-                    .case_insensitive(true)
+                    .case_insensitive(!case_sensitive)
                     .build()
             })
             .transpose()?,
+        // An empty '--extension' value is how users ask to match files without any extension at
+        // all, since that can't be expressed as a suffix pattern in 'extensions' above.
+        match_no_extension: matches
+            .values_of("extension")
+            .map_or(false, |mut exts| exts.any(|e| e.is_empty())),
         command: command.map(Arc::new),
         exclude_patterns: matches
             .values_of("exclude")
             .map(|v| v.map(|p| String::from("!") + p).collect())
             .unwrap_or_else(Vec::new),
+        exclude_regex: matches
+            .values_of("exclude-regex")
+            .map(RegexSetBuilder::new)
+            .map(|builder| builder.build())
+            .transpose()?,
+        and_patterns,
+        not_patterns,
         ignore_files: matches
             .values_of("ignore-file")
             .map(|vs| vs.map(PathBuf::from).collect())
             .unwrap_or_else(Vec::new),
+        ignore_file_names: matches
+            .values_of("ignore-file-name")
+            .map(|vs| vs.map(String::from).collect())
+            .unwrap_or_else(Vec::new),
         size_constraints: size_limits,
         time_constraints,
         #[cfg(unix)]
         owner_constraint,
+        #[cfg(unix)]
+        permission_constraint,
+        #[cfg(unix)]
+        same_file_filter,
         show_filesystem_errors: matches.is_present("show-errors"),
+        quiet: matches.is_present("quiet"),
+        stats: matches.is_present("stats"),
+        progress: matches.is_present("progress") && atty::is(Stream::Stderr),
         path_separator,
         max_results: matches
             .value_of("max-results")
@@ -431,6 +636,28 @@ fn run() -> Result<ExitCode> {
                     None
                 }
             }),
+        output_format: if matches.value_of("format") == Some("json") {
+            OutputFormat::Json
+        } else {
+            OutputFormat::Standard
+        },
+        sort_by: matches.value_of("sort").map(|criterion| match criterion {
+            "path" => SortBy::Path,
+            "name" => SortBy::Name,
+            "size" => SortBy::Size,
+            "modified" => SortBy::ModifiedTime,
+            _ => unreachable!("Invalid value for --sort"),
+        }),
+        sort_reverse: matches.is_present("sort-reverse"),
+        classify: matches.is_present("classify"),
+        trailing_slash: matches.is_present("trailing-slash"),
+        canonicalize: matches.is_present("canonicalize"),
+        strip_cwd_prefix: roots_are_cwd || matches.is_present("strip-cwd-prefix"),
+        relative_to: matches
+            .value_of("relative-to")
+            .map(|path| filesystem::canonicalize_or_absolute(Path::new(path))),
+        quote: matches.is_present("quote"),
+        hyperlink: matches.is_present("hyperlink") && interactive_terminal,
     };
 
     if cfg!(unix)
@@ -1,13 +1,15 @@
 use std::{path::PathBuf, sync::Arc, time::Duration};
 
 use lscolors::LsColors;
-use regex::bytes::RegexSet;
+use regex::bytes::{Regex, RegexSet};
 
 use crate::exec::CommandTemplate;
 use crate::filetypes::FileTypes;
 #[cfg(unix)]
-use crate::filter::OwnerFilter;
+use crate::filter::{OwnerFilter, PermFilter, SameFileFilter};
 use crate::filter::{SizeFilter, TimeFilter};
+use crate::output::OutputFormat;
+use crate::walk::{FollowMode, SortBy};
 
 /// Configuration options for *fd*.
 pub struct Options {
@@ -18,20 +20,50 @@ pub struct Options {
     /// name).
     pub search_full_path: bool,
 
+    /// Whether a match against either the base name or the full path counts as a match, instead
+    /// of requiring the whole path to satisfy the pattern like `search_full_path` does.
+    pub search_full_path_or_name: bool,
+
+    /// Whether a symlink entry is matched against its resolved target path instead of its own
+    /// name or path. Broken symlinks never match under this mode. Has no effect on non-symlink
+    /// entries.
+    pub match_symlink_target: bool,
+
+    /// Whether to suppress per-entry output and print only the number of matches once the
+    /// search has finished.
+    pub count: bool,
+
     /// Whether to ignore hidden files and directories (or not).
     pub ignore_hidden: bool,
 
-    /// Whether to respect `.fdignore` files or not.
+    /// Whether to respect "dot-ignore" files: `.ignore`, `.fdignore`, and any filename
+    /// registered via `--ignore-file-name`. Independent of `read_vcsignore`, so `.gitignore`
+    /// can still be honored with this turned off (`--no-ignore-dot`), and vice versa.
     pub read_fdignore: bool,
 
     /// Whether to respect VCS ignore files (`.gitignore`, ..) or not.
     pub read_vcsignore: bool,
 
+    /// Whether to respect git's global gitignore file (`core.excludesFile`), independently of
+    /// local `.gitignore` and `.git/info/exclude` handling.
+    pub read_global_vcsignore: bool,
+
     /// Whether to respect the global ignore file or not.
     pub read_global_ignore: bool,
 
-    /// Whether to follow symlinks or not.
-    pub follow_links: bool,
+    /// Whether to look for ignore files in parent directories, above the search root, or only
+    /// within the search root itself.
+    pub read_parent_ignore: bool,
+
+    /// Whether and which symlinks to follow: not at all, only search roots that are themselves
+    /// symlinks (the default), or every symlink encountered during the search.
+    pub follow: FollowMode,
+
+    /// Whether results found under a search root that is itself a symlink should be displayed
+    /// with that root rewritten to its resolved (canonicalized) target, instead of the symlink
+    /// path that was given on the command line. Has no effect on roots that aren't symlinks, or
+    /// on broken symlink roots (which have nothing to resolve to).
+    pub resolve_root_symlink: bool,
 
     /// Whether to limit the search to starting file system or not.
     pub one_file_system: bool,
@@ -48,6 +80,10 @@ pub struct Options {
     /// The minimum depth for reported entries, or `None`.
     pub min_depth: Option<usize>,
 
+    /// Whether `max_depth`/`min_depth` count from the current working directory instead of from
+    /// each search root independently.
+    pub depth_from_cwd: bool,
+
     /// Whether to stop traversing into matching directories.
     pub prune: bool,
 
@@ -56,9 +92,14 @@ pub struct Options {
 
     /// Time to buffer results internally before streaming to the console. This is useful to
     /// provide a sorted output, in case the total execution time is shorter than
-    /// `max_buffer_time`.
+    /// `max_buffer_time`. A value of `Duration::ZERO` disables buffering entirely, printing
+    /// each result as soon as it is found.
     pub max_buffer_time: Option<Duration>,
 
+    /// Whether to bypass the internal buffering entirely and flush the output after every
+    /// single result, regardless of `max_buffer_time`.
+    pub no_buffer: bool,
+
     /// `None` if the output should not be colorized. Otherwise, a `LsColors` instance that defines
     /// how to style different filetypes.
     pub ls_colors: Option<LsColors>,
@@ -70,20 +111,44 @@ pub struct Options {
     /// set to `Some(..)`, only the types that are specified are shown.
     pub file_types: Option<FileTypes>,
 
+    /// Types of file to exclude from the search (via `--type-not`), subtracted from whatever
+    /// `file_types` would otherwise include.
+    pub exclude_file_types: Option<FileTypes>,
+
     /// The extension to search for. Only entries matching the extension will be included.
     ///
     /// The value (if present) will be a lowercase string without leading dots.
     pub extensions: Option<RegexSet>,
 
+    /// Whether entries without a file extension (e.g. `Makefile`) should be considered a match
+    /// for the `--extension` filter, in addition to (or instead of) `extensions`.
+    pub match_no_extension: bool,
+
     /// If a value is supplied, each item found will be used to generate and execute commands.
     pub command: Option<Arc<CommandTemplate>>,
 
     /// A list of glob patterns that should be excluded from the search.
     pub exclude_patterns: Vec<String>,
 
+    /// Patterns matched against the full (relative) path of each entry; any match excludes
+    /// the entry, independently of `exclude_patterns`.
+    pub exclude_regex: Option<RegexSet>,
+
+    /// Additional patterns (from `--and`) that must all match, together with the primary
+    /// search pattern, for an entry to be included.
+    pub and_patterns: Vec<Regex>,
+
+    /// Patterns (from `--not`) that exclude an entry if any of them match, complementing the
+    /// primary search pattern. Matched the same way as the primary pattern (name/full path).
+    pub not_patterns: Vec<Regex>,
+
     /// A list of custom ignore files.
     pub ignore_files: Vec<PathBuf>,
 
+    /// Additional custom ignore-filenames (set via `--ignore-file-name`) that the walker
+    /// should honor, alongside the built-in `.gitignore` and `.fdignore`.
+    pub ignore_file_names: Vec<String>,
+
     /// The given constraints on the size of returned files
     pub size_constraints: Vec<SizeFilter>,
 
@@ -94,12 +159,70 @@ pub struct Options {
     /// User/group ownership constraint
     pub owner_constraint: Option<OwnerFilter>,
 
+    #[cfg(unix)]
+    /// Permission bits constraint
+    pub permission_constraint: Option<PermFilter>,
+
+    #[cfg(unix)]
+    /// Only show entries that are hardlinks of the given reference file
+    pub same_file_filter: Option<SameFileFilter>,
+
     /// Whether or not to display filesystem errors
     pub show_filesystem_errors: bool,
 
+    /// Whether to suppress all diagnostic messages printed to stderr. Takes precedence over
+    /// `show_filesystem_errors`.
+    pub quiet: bool,
+
+    /// Whether to print a summary of the search (matches by file type, directories visited,
+    /// elapsed time) to stderr once the search has completed.
+    pub stats: bool,
+
+    /// Whether to periodically print the number of directories visited so far to stderr, as a
+    /// self-erasing, carriage-return-updating line, for feedback during slow, large traversals.
+    pub progress: bool,
+
     /// The separator used to print file paths.
     pub path_separator: Option<String>,
 
     /// The maximum number of search results
     pub max_results: Option<usize>,
+
+    /// The format used to print search results.
+    pub output_format: OutputFormat,
+
+    /// The criterion used to sort results, or `None` if results should not be sorted and
+    /// instead streamed to the console as they are found.
+    pub sort_by: Option<SortBy>,
+
+    /// Whether to reverse the order produced by `sort_by`.
+    pub sort_reverse: bool,
+
+    /// Whether to append a file-type indicator (`/`, `@`, `*`) to each printed path.
+    pub classify: bool,
+
+    /// Whether to append a trailing `/` to directory results. Superseded by `classify`, whose
+    /// own indicator for directories is already `/`.
+    pub trailing_slash: bool,
+
+    /// Whether to canonicalize each result, resolving symlinks and `..` components.
+    pub canonicalize: bool,
+
+    /// Whether to strip the leading `./` from each result. This only ever has an effect when
+    /// every search root is the current directory, since other roots never produce a leading
+    /// `./` component in the first place.
+    pub strip_cwd_prefix: bool,
+
+    /// If set, each result is shown relative to this (already absolute) directory instead of
+    /// relative to the current working directory. Results that don't lie under it fall back to
+    /// being shown as an absolute path.
+    pub relative_to: Option<PathBuf>,
+
+    /// Whether to wrap each printed path in single quotes, escaping any embedded single quotes,
+    /// so that the output is safe to use in a shell.
+    pub quote: bool,
+
+    /// Whether to wrap each printed path in an OSC 8 escape sequence, turning it into a
+    /// clickable `file://` hyperlink in terminals that support it.
+    pub hyperlink: bool,
 }
@@ -1,33 +1,151 @@
-use std::io::{self, StdoutLock, Write};
+use std::io::{self, BufWriter, StdoutLock, Write};
 use std::path::{Path, PathBuf};
 use std::process;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::UNIX_EPOCH;
 
-use lscolors::{LsColors, Style};
+use lscolors::{Indicator, LsColors, Style};
 
+use crate::error::print_error;
 use crate::exit_codes::ExitCode;
-use crate::filesystem::strip_current_dir;
+use crate::filesystem::{
+    canonicalize_or_absolute, is_executable, osstr_to_bytes, path_relative_from,
+    strip_current_dir,
+};
 use crate::options::Options;
 
+/// The format that search results are printed in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// One plain path per line (the default).
+    Standard,
+    /// Newline-delimited JSON objects with path and metadata fields.
+    Json,
+}
+
 fn replace_path_separator(path: &str, new_path_separator: &str) -> String {
     path.replace(std::path::MAIN_SEPARATOR, &new_path_separator)
 }
 
+/// Determines the `ls -F`-style indicator to append to a path for `--classify`: `/` for
+/// directories, `@` for symbolic links, `*` for executable files, or nothing otherwise.
+fn classify_indicator(path: &Path) -> &'static str {
+    match path.symlink_metadata() {
+        Ok(ref md) if md.file_type().is_symlink() => "@",
+        Ok(ref md) if md.file_type().is_dir() => "/",
+        Ok(ref md) if is_executable(path, md) => "*",
+        _ => "",
+    }
+}
+
+/// Determines the indicator to append to a printed path, for `--classify` or `--trailing-slash`.
+/// `--classify` takes precedence, since its own indicator for directories is already `/`. Does
+/// not double up the slash if `path` already ends with a path separator.
+fn suffix_indicator(path: &Path, config: &Options) -> &'static str {
+    if config.classify {
+        classify_indicator(path)
+    } else if config.trailing_slash
+        && path
+            .symlink_metadata()
+            .map_or(false, |md| md.file_type().is_dir())
+        && !path.as_os_str().to_string_lossy().ends_with(std::path::MAIN_SEPARATOR)
+    {
+        "/"
+    } else {
+        ""
+    }
+}
+
+/// Percent-encode the bytes of `path` that aren't allowed unescaped in a `file://` URI path,
+/// per RFC 3986's unreserved character set.
+fn percent_encode_path(path: &Path) -> String {
+    let bytes = osstr_to_bytes(path.as_os_str());
+    let mut encoded = String::with_capacity(bytes.len());
+    for &byte in bytes.iter() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// Builds the `file://` URI that a hyperlink to `path` should point at.
+fn hyperlink_uri(path: &Path) -> String {
+    format!(
+        "file://{}",
+        percent_encode_path(&canonicalize_or_absolute(path))
+    )
+}
+
+/// Writes the OSC 8 escape sequence that starts a terminal hyperlink pointing at the
+/// `file://` URI for `path`.
+fn write_hyperlink_start(stdout: &mut BufWriter<StdoutLock>, path: &Path) -> io::Result<()> {
+    write!(stdout, "\x1b]8;;{}\x1b\\", hyperlink_uri(path))
+}
+
+/// Writes the OSC 8 escape sequence that closes a terminal hyperlink opened with
+/// `write_hyperlink_start`.
+fn write_hyperlink_end(stdout: &mut BufWriter<StdoutLock>) -> io::Result<()> {
+    write!(stdout, "\x1b]8;;\x1b\\")
+}
+
+/// If `path` is a symlink, writes ` -> target` to `stdout`, colorizing the target with the
+/// `ln` (symbolic link) style, or the `or` (orphan) style if the link is broken.
+fn write_symlink_target(
+    stdout: &mut BufWriter<StdoutLock>,
+    path: &Path,
+    ls_colors: &LsColors,
+) -> io::Result<()> {
+    if let Ok(target) = std::fs::read_link(path) {
+        let indicator = if path.metadata().is_ok() {
+            Indicator::SymbolicLink
+        } else {
+            Indicator::OrphanedSymbolicLink
+        };
+
+        let style = ls_colors
+            .style_for_indicator(indicator)
+            .map(Style::to_ansi_term_style)
+            .unwrap_or_default();
+
+        write!(stdout, " -> {}", style.paint(target.to_string_lossy()))?;
+    }
+
+    Ok(())
+}
+
 // TODO: this function is performance critical and can probably be optimized
 pub fn print_entry(
-    stdout: &mut StdoutLock,
+    stdout: &mut BufWriter<StdoutLock>,
     entry: &PathBuf,
     config: &Options,
     wants_to_quit: &Arc<AtomicBool>,
 ) {
-    let path = if entry.is_absolute() {
+    let canonicalized_path;
+    let relative_path;
+    let path = if config.canonicalize {
+        canonicalized_path = canonicalize_or_absolute(entry);
+        canonicalized_path.as_path()
+    } else if let Some(ref base) = config.relative_to {
+        relative_path = path_relative_from(entry, base);
+        relative_path.as_path()
+    } else if entry.is_absolute() {
         entry.as_path()
-    } else {
+    } else if config.strip_cwd_prefix {
         strip_current_dir(entry)
+    } else {
+        entry.as_path()
     };
 
-    let r = if let Some(ref ls_colors) = config.ls_colors {
+    let r = if config.output_format == OutputFormat::Json {
+        print_entry_json(stdout, path)
+    } else if config.quote {
+        print_entry_quoted(stdout, path, config)
+    } else if let Some(ref ls_colors) = config.ls_colors {
         print_entry_colorized(stdout, path, config, ls_colors, &wants_to_quit)
     } else {
         print_entry_uncolorized(stdout, path, config)
@@ -39,9 +157,38 @@ pub fn print_entry(
     }
 }
 
+fn print_entry_json(stdout: &mut BufWriter<StdoutLock>, path: &Path) -> io::Result<()> {
+    let (path_string, path_lossy) = match path.to_str() {
+        Some(s) => (s.to_owned(), false),
+        None => (path.to_string_lossy().into_owned(), true),
+    };
+
+    let metadata = path.symlink_metadata();
+    let (file_type, size, modified) = match metadata {
+        Ok(ref md) if md.file_type().is_dir() => ("directory", md.len(), md.modified().ok()),
+        Ok(ref md) if md.file_type().is_symlink() => ("symlink", md.len(), md.modified().ok()),
+        Ok(ref md) => ("file", md.len(), md.modified().ok()),
+        Err(_) => ("unknown", 0, None),
+    };
+
+    let modified = modified
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| humantime::format_rfc3339(UNIX_EPOCH + d).to_string());
+
+    let entry = serde_json::json!({
+        "path": path_string,
+        "path_lossy": path_lossy,
+        "file_type": file_type,
+        "size": size,
+        "modified": modified,
+    });
+
+    writeln!(stdout, "{}", entry)
+}
+
 // TODO: this function is performance critical and can probably be optimized
 fn print_entry_colorized(
-    stdout: &mut StdoutLock,
+    stdout: &mut BufWriter<StdoutLock>,
     path: &Path,
     config: &Options,
     ls_colors: &LsColors,
@@ -49,6 +196,10 @@ fn print_entry_colorized(
 ) -> io::Result<()> {
     let default_style = ansi_term::Style::default();
 
+    if config.hyperlink {
+        write_hyperlink_start(stdout, path)?;
+    }
+
     // Traverse the path and colorize each component
     for (component, style) in ls_colors.style_for_path_components(path) {
         let style = style
@@ -68,6 +219,16 @@ fn print_entry_colorized(
         }
     }
 
+    write!(stdout, "{}", suffix_indicator(path, config))?;
+
+    if path.symlink_metadata().map_or(false, |md| md.file_type().is_symlink()) {
+        write_symlink_target(stdout, path, ls_colors)?;
+    }
+
+    if config.hyperlink {
+        write_hyperlink_end(stdout)?;
+    }
+
     if config.null_separator {
         write!(stdout, "\0")
     } else {
@@ -75,9 +236,60 @@ fn print_entry_colorized(
     }
 }
 
+/// Wrap `path` in single quotes, escaping any single quotes it contains, so that the result is
+/// safe to use as a single shell word. Paths that aren't valid UTF-8 fall back to a lossy
+/// conversion, with a warning printed to stderr (unless `--quiet` was given).
+fn shell_quote(path: &Path, config: &Options) -> String {
+    let path_string = match path.to_str() {
+        Some(s) => s.into(),
+        None => {
+            if !config.quiet {
+                print_error(format!(
+                    "Path '{}' is not valid UTF-8, falling back to lossy quoting.",
+                    path.to_string_lossy()
+                ));
+            }
+            path.to_string_lossy().into_owned()
+        }
+    };
+
+    let mut quoted = String::with_capacity(path_string.len() + 2);
+    quoted.push('\'');
+    for c in path_string.chars() {
+        if c == '\'' {
+            quoted.push_str("'\\''");
+        } else {
+            quoted.push(c);
+        }
+    }
+    quoted.push('\'');
+    quoted
+}
+
+fn print_entry_quoted(
+    stdout: &mut BufWriter<StdoutLock>,
+    path: &Path,
+    config: &Options,
+) -> io::Result<()> {
+    let separator = if config.null_separator { "\0" } else { "\n" };
+
+    let mut path_string = path.to_string_lossy().into_owned();
+    if let Some(ref separator) = config.path_separator {
+        path_string = replace_path_separator(&path_string, &separator);
+    }
+
+    write!(
+        stdout,
+        "{}{}{}",
+        shell_quote(Path::new(&path_string), config),
+        suffix_indicator(path, config),
+        separator
+    )
+}
+
 // TODO: this function is performance critical and can probably be optimized
 fn print_entry_uncolorized_base(
-    stdout: &mut StdoutLock,
+    stdout: &mut BufWriter<StdoutLock>,
     path: &Path,
     config: &Options,
 ) -> io::Result<()> {
@@ -87,12 +299,23 @@ fn print_entry_uncolorized_base(
     if let Some(ref separator) = config.path_separator {
         *path_string.to_mut() = replace_path_separator(&path_string, &separator);
     }
-    write!(stdout, "{}{}", path_string, separator)
+
+    if config.hyperlink {
+        write_hyperlink_start(stdout, path)?;
+    }
+
+    write!(stdout, "{}{}", path_string, suffix_indicator(path, config))?;
+
+    if config.hyperlink {
+        write_hyperlink_end(stdout)?;
+    }
+
+    write!(stdout, "{}", separator)
 }
 
 #[cfg(not(unix))]
 fn print_entry_uncolorized(
-    stdout: &mut StdoutLock,
+    stdout: &mut BufWriter<StdoutLock>,
     path: &Path,
     config: &Options,
 ) -> io::Result<()> {
@@ -101,13 +324,17 @@ fn print_entry_uncolorized(
 
 #[cfg(unix)]
 fn print_entry_uncolorized(
-    stdout: &mut StdoutLock,
+    stdout: &mut BufWriter<StdoutLock>,
     path: &Path,
     config: &Options,
 ) -> io::Result<()> {
     use std::os::unix::ffi::OsStrExt;
 
-    if config.interactive_terminal || config.path_separator.is_some() {
+    if config.interactive_terminal
+        || config.path_separator.is_some()
+        || config.classify
+        || config.trailing_slash
+    {
         // Fall back to the base implementation
         print_entry_uncolorized_base(stdout, path, config)
     } else {
@@ -117,3 +344,28 @@ fn print_entry_uncolorized(
         stdout.write_all(separator)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percent_encode_path_escapes_spaces_and_leaves_slashes() {
+        assert_eq!(
+            percent_encode_path(Path::new("/tmp/a dir/file.txt")),
+            "/tmp/a%20dir/file.txt"
+        );
+    }
+
+    #[test]
+    fn hyperlink_uri_is_a_percent_encoded_file_url() {
+        let uri = hyperlink_uri(Path::new("/tmp/a dir/file.txt"));
+        assert_eq!(uri, "file:///tmp/a%20dir/file.txt");
+    }
+
+    #[test]
+    fn replace_path_separator_rewrites_the_platform_separator() {
+        let path = format!("one{0}two{0}three", std::path::MAIN_SEPARATOR);
+        assert_eq!(replace_path_separator(&path, "/"), "one/two/three");
+    }
+}
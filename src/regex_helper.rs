@@ -1,36 +1,63 @@
+use regex_syntax::ast::{self, Ast};
 use regex_syntax::hir::Hir;
 use regex_syntax::ParserBuilder;
 
 /// Determine if a regex pattern contains a literal uppercase character.
+///
+/// This only inspects literal characters and explicit character-class ranges (e.g. `[A-Z]`),
+/// not shorthand classes like `\D`/`\W`/`\p{L}`: those match by Unicode property rather than by
+/// naming a specific letter, so an uppercase letter happening to be inside such a class (which is
+/// the common case, since most non-digit/non-word classes contain nearly the whole alphabet)
+/// shouldn't force case-sensitive matching.
 pub fn pattern_has_uppercase_char(pattern: &str) -> bool {
-    let mut parser = ParserBuilder::new().allow_invalid_utf8(true).build();
-
-    parser
+    ast::parse::Parser::new()
         .parse(pattern)
-        .map(|hir| hir_has_uppercase_char(&hir))
+        .map(|ast| ast_has_uppercase_char(&ast))
         .unwrap_or(false)
 }
 
-/// Determine if a regex expression contains a literal uppercase character.
-fn hir_has_uppercase_char(hir: &Hir) -> bool {
-    use regex_syntax::hir::*;
+/// Determine if a regex syntax tree contains a literal uppercase character.
+fn ast_has_uppercase_char(ast: &Ast) -> bool {
+    match *ast {
+        Ast::Literal(ref lit) => lit.c.is_uppercase(),
+        Ast::Class(ast::Class::Bracketed(ref class)) => class_set_has_uppercase_char(&class.kind),
+        Ast::Repetition(ref rep) => ast_has_uppercase_char(&rep.ast),
+        Ast::Group(ref group) => ast_has_uppercase_char(&group.ast),
+        Ast::Concat(ref concat) => concat.asts.iter().any(ast_has_uppercase_char),
+        Ast::Alternation(ref alt) => alt.asts.iter().any(ast_has_uppercase_char),
+        // `\D`, `\W`, `\p{L}`, ... match by Unicode property, not by naming a specific letter.
+        Ast::Class(ast::Class::Perl(_)) | Ast::Class(ast::Class::Unicode(_)) => false,
+        _ => false,
+    }
+}
 
-    match *hir.kind() {
-        HirKind::Literal(Literal::Unicode(c)) => c.is_uppercase(),
-        HirKind::Literal(Literal::Byte(b)) => char::from(b).is_uppercase(),
-        HirKind::Class(Class::Unicode(ref ranges)) => ranges
-            .iter()
-            .any(|r| r.start().is_uppercase() || r.end().is_uppercase()),
-        HirKind::Class(Class::Bytes(ref ranges)) => ranges
-            .iter()
-            .any(|r| char::from(r.start()).is_uppercase() || char::from(r.end()).is_uppercase()),
-        HirKind::Group(Group { ref hir, .. }) | HirKind::Repetition(Repetition { ref hir, .. }) => {
-            hir_has_uppercase_char(hir)
+/// Determine if a bracketed character class (e.g. `[a-zA-Z\d]`) contains a literal uppercase
+/// character or an explicit range spanning one, ignoring any nested shorthand classes.
+fn class_set_has_uppercase_char(set: &ast::ClassSet) -> bool {
+    match *set {
+        ast::ClassSet::Item(ref item) => class_set_item_has_uppercase_char(item),
+        ast::ClassSet::BinaryOp(ref op) => {
+            class_set_has_uppercase_char(&op.lhs) || class_set_has_uppercase_char(&op.rhs)
+        }
+    }
+}
+
+fn class_set_item_has_uppercase_char(item: &ast::ClassSetItem) -> bool {
+    match *item {
+        ast::ClassSetItem::Literal(ref lit) => lit.c.is_uppercase(),
+        ast::ClassSetItem::Range(ref range) => {
+            range.start.c.is_uppercase() || range.end.c.is_uppercase()
         }
-        HirKind::Concat(ref hirs) | HirKind::Alternation(ref hirs) => {
-            hirs.iter().any(hir_has_uppercase_char)
+        ast::ClassSetItem::Bracketed(ref class) => class_set_has_uppercase_char(&class.kind),
+        ast::ClassSetItem::Union(ref union) => {
+            union.items.iter().any(class_set_item_has_uppercase_char)
         }
-        _ => false,
+        // Shorthand classes (`\D`, `\p{L}`, POSIX `[:upper:]`, ...) match by Unicode property,
+        // not by naming a specific letter.
+        ast::ClassSetItem::Perl(_) | ast::ClassSetItem::Unicode(_) | ast::ClassSetItem::Ascii(_) => {
+            false
+        }
+        ast::ClassSetItem::Empty(_) => false,
     }
 }
 
@@ -90,6 +117,31 @@ fn pattern_has_uppercase_char_advanced() {
     assert!(!pattern_has_uppercase_char(r"carg\x6F"));
 }
 
+#[test]
+fn pattern_has_uppercase_char_unicode() {
+    // Accented uppercase letters are outside the ASCII range, but should still trigger
+    // smart-case, since this checks Unicode case properties rather than just ASCII.
+    assert!(pattern_has_uppercase_char("Ärger"));
+    assert!(pattern_has_uppercase_char("caf\u{c9}")); // "cafÉ"
+
+    assert!(!pattern_has_uppercase_char("ärger"));
+    assert!(!pattern_has_uppercase_char("caf\u{e9}")); // "café"
+}
+
+#[test]
+fn pattern_has_uppercase_char_ignores_shorthand_classes() {
+    // '\D'/'\W' match almost the entire alphabet (including uppercase letters) by Unicode
+    // property, not by naming a specific letter, so they shouldn't trigger case-sensitivity.
+    assert!(!pattern_has_uppercase_char(r"\D"));
+    assert!(!pattern_has_uppercase_char(r"\W"));
+    assert!(!pattern_has_uppercase_char(r"foo\Dbar"));
+
+    // An explicit uppercase literal still triggers case-sensitivity, including alongside a
+    // shorthand class.
+    assert!(pattern_has_uppercase_char("Foo"));
+    assert!(pattern_has_uppercase_char(r"Foo\D"));
+}
+
 #[test]
 fn matches_strings_with_leading_dot_simple() {
     assert!(pattern_matches_strings_with_leading_dot("^\\.gitignore"));
@@ -1,10 +1,11 @@
 use std::borrow::Cow;
-use std::ffi::OsStr;
+use std::ffi::OsString;
+use std::fs;
 use std::fs::{FileType, Metadata};
-use std::io;
+use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 use std::process;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::mpsc::{channel, Receiver, Sender};
 use std::sync::{Arc, Mutex};
 use std::thread;
@@ -22,6 +23,87 @@ use crate::filesystem;
 use crate::options::Options;
 use crate::output;
 
+/// The criterion used to sort search results, set via `--sort`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortBy {
+    Path,
+    Name,
+    Size,
+    ModifiedTime,
+}
+
+/// Whether and which symlinks are followed during a search, set via `--follow`/`--follow-roots`.
+/// Mirrors the distinction `find` draws between `-P`, `-H` and `-L`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FollowMode {
+    /// Don't descend into any symlink, including search roots that are themselves symlinks.
+    None,
+    /// Descend into search roots that are symlinks, but not symlinks encountered elsewhere
+    /// during the search. This is fd's default.
+    RootsOnly,
+    /// Descend into every symlink encountered, both search roots and nested ones.
+    All,
+}
+
+/// Whether a search root entry that is a symlink should be skipped instead of descended into,
+/// based on the configured `FollowMode`. Only `FollowMode::None` refuses to descend into a
+/// symlinked root; `RootsOnly` and `All` both follow it (they differ on symlinks found deeper in
+/// the tree, which `ignore::WalkBuilder`'s own `follow_links` setting already handles).
+fn should_skip_symlink_root(follow: FollowMode, root_is_symlink: bool) -> bool {
+    follow == FollowMode::None && root_is_symlink
+}
+
+/// Whether `path`, used as a search root, is itself a symlink. `ignore::DirEntry::path_is_symlink`
+/// can't be used for this: `walkdir` always resolves the very first path it's given in order to
+/// determine its file type, so the root entry's cached symlink bit is never set, even when the
+/// root really is a symlink.
+fn root_is_symlink(path: &Path) -> bool {
+    fs::symlink_metadata(path)
+        .map(|m| m.file_type().is_symlink())
+        .unwrap_or(false)
+}
+
+/// Wraps `f` so that it is only ever called once; every call to the returned closure clones and
+/// returns the cached result of that first call. Used to share a single, possibly expensive,
+/// lookup (e.g. a `metadata()` syscall) across several independent checks on the same entry.
+fn memoized<T: Clone>(mut f: impl FnMut() -> Option<T>) -> impl FnMut() -> Option<T> {
+    let mut cached: Option<T> = None;
+    move || {
+        if cached.is_none() {
+            cached = f();
+        }
+        cached.clone()
+    }
+}
+
+/// Sorts `entries` in place according to the given `SortBy` criterion, re-querying metadata
+/// as needed since only the path is retained in the result buffer.
+fn sort_by_criterion(entries: &mut [PathBuf], sort_by: SortBy, reverse: bool) {
+    match sort_by {
+        SortBy::Path => entries.sort(),
+        SortBy::Name => entries.sort_by(|a, b| a.file_name().cmp(&b.file_name())),
+        SortBy::Size => entries.sort_by_key(|p| p.symlink_metadata().map_or(0, |m| m.len())),
+        SortBy::ModifiedTime => entries.sort_by_key(|p| {
+            p.symlink_metadata()
+                .and_then(|m| m.modified())
+                .unwrap_or(time::UNIX_EPOCH)
+        }),
+    }
+
+    if reverse {
+        entries.reverse();
+    }
+}
+
+/// Sorts a buffered chunk of results alphabetically before it is flushed early (i.e. before the
+/// full result set has been collected), for readability. Only applied for interactive terminal
+/// output, since scripts consuming piped output expect entries in the order they were found.
+fn sort_buffered_chunk_if_interactive(buffer: &mut [PathBuf], interactive_terminal: bool) {
+    if interactive_terminal {
+        buffer.sort();
+    }
+}
+
 /// The receiver thread can either be buffering results or directly streaming to the console.
 enum ReceiverMode {
     /// Receiver is still buffering in order to sort the results, if the search finishes fast
@@ -41,6 +123,61 @@ pub enum WorkerResult {
 /// Maximum size of the output buffer before flushing results to the console
 pub const MAX_BUFFER_LENGTH: usize = 1000;
 
+/// Aggregate counters for `--stats` and `--progress`, shared and updated concurrently by the
+/// parallel walker.
+#[derive(Default)]
+pub struct SearchStats {
+    /// Number of directories the walker descended into, whether or not they matched.
+    pub dirs_visited: AtomicUsize,
+    /// Number of matched entries that are regular files.
+    pub matched_files: AtomicUsize,
+    /// Number of matched entries that are directories.
+    pub matched_dirs: AtomicUsize,
+    /// Number of matched entries that are symbolic links.
+    pub matched_symlinks: AtomicUsize,
+    /// Number of matched entries that are neither a file, a directory, nor a symlink.
+    pub matched_other: AtomicUsize,
+}
+
+/// While `done` is unset, periodically overwrites the same stderr line with the number of
+/// directories visited so far, for interactive feedback during slow, large traversals. Once
+/// `done` is set, erases its own line so it doesn't linger in front of `--stats`' summary or
+/// leave stray output behind.
+fn print_progress_until_done(stats: &SearchStats, done: &AtomicBool) {
+    let mut last_line_len = 0;
+
+    while !done.load(Ordering::Relaxed) {
+        let line = format!("{} directories visited", stats.dirs_visited.load(Ordering::Relaxed));
+        eprint!("\r{}", line);
+        last_line_len = last_line_len.max(line.len());
+        let _ = io::stderr().flush();
+
+        thread::sleep(time::Duration::from_millis(100));
+    }
+
+    eprint!("\r{}\r", " ".repeat(last_line_len));
+    let _ = io::stderr().flush();
+}
+
+/// Expands a single brace-alternation group in a `--exclude` pattern, e.g. `*.{tmp,bak}` becomes
+/// `["*.tmp", "*.bak"]`. The gitignore-style globs used for excludes don't support shell-style
+/// brace expansion, so it's handled here instead, before the pattern reaches the `ignore` crate.
+/// Patterns without a brace group are returned unchanged, as a single-element vector.
+fn expand_brace_pattern(pattern: &str) -> Vec<String> {
+    if let (Some(open), Some(close)) = (pattern.find('{'), pattern.rfind('}')) {
+        if open < close {
+            let prefix = &pattern[..open];
+            let suffix = &pattern[close + 1..];
+            return pattern[open + 1..close]
+                .split(',')
+                .map(|alternative| format!("{}{}{}", prefix, alternative, suffix))
+                .collect();
+        }
+    }
+
+    vec![pattern.to_owned()]
+}
+
 /// Recursively scan the given search path for files / pathnames matching the pattern.
 ///
 /// If the `--exec` argument was supplied, this will create a thread pool for executing
@@ -53,33 +190,76 @@ pub fn scan(path_vec: &[PathBuf], pattern: Arc<Regex>, config: Arc<Options>) ->
         .expect("Error: Path vector can not be empty");
     let (tx, rx) = channel();
 
+    let stats = (config.stats || config.progress).then(|| Arc::new(SearchStats::default()));
+    let stats_start_time = time::Instant::now();
+
     let mut override_builder = OverrideBuilder::new(first_path_buf.as_path());
 
     for pattern in &config.exclude_patterns {
-        override_builder
-            .add(pattern)
-            .map_err(|e| anyhow!("Malformed exclude pattern: {}", e))?;
+        for expanded_pattern in expand_brace_pattern(pattern) {
+            override_builder
+                .add(&expanded_pattern)
+                .map_err(|e| anyhow!("Malformed exclude pattern: {}", e))?;
+        }
     }
     let overrides = override_builder
         .build()
         .map_err(|_| anyhow!("Mismatch in exclude patterns"))?;
 
-    let mut walker = WalkBuilder::new(first_path_buf.as_path());
+    // On Windows, traverse through the '\\?\' extended-length form so that deep trees aren't
+    // subject to the legacy 260-character 'MAX_PATH' limit; entry paths are normalized back
+    // before they're matched against or displayed. A no-op on other platforms.
+    let mut walker = WalkBuilder::new(filesystem::ensure_long_path_prefix(first_path_buf.clone()));
     walker
         .hidden(config.ignore_hidden)
         .ignore(config.read_fdignore)
-        .parents(config.read_fdignore || config.read_vcsignore)
+        .parents((config.read_fdignore || config.read_vcsignore) && config.read_parent_ignore)
         .git_ignore(config.read_vcsignore)
-        .git_global(config.read_vcsignore)
+        .git_global(config.read_vcsignore && config.read_global_vcsignore)
         .git_exclude(config.read_vcsignore)
         .overrides(overrides)
-        .follow_links(config.follow_links)
+        .follow_links(config.follow == FollowMode::All)
         // No need to check for supported platforms, option is unavailable on unsupported ones
         .same_file_system(config.one_file_system)
-        .max_depth(config.max_depth);
+        // When '--depth-from cwd' is used, depth is no longer counted per search root, so the
+        // bound is enforced manually in 'spawn_senders' instead of here.
+        .max_depth(if config.depth_from_cwd {
+            None
+        } else {
+            config.max_depth
+        });
+
+    // The number of path components in the current working directory, used as the baseline for
+    // depth calculations when '--depth-from cwd' is in effect.
+    let cwd_components = config
+        .depth_from_cwd
+        .then(|| std::env::current_dir())
+        .transpose()?
+        .map(|cwd| cwd.components().count());
+
+    // When '--resolve-root-symlink' is set, results found under a search root that is itself a
+    // symlink are displayed with that root rewritten to its resolved (canonicalized) target,
+    // instead of the symlink path. Broken symlinks are left as-is, since they have nothing to
+    // resolve to.
+    let root_rewrites: Vec<(PathBuf, PathBuf)> = if config.resolve_root_symlink {
+        path_vec
+            .iter()
+            .filter(|root| {
+                root.symlink_metadata()
+                    .map_or(false, |m| m.file_type().is_symlink())
+            })
+            .filter_map(|root| root.canonicalize().ok().map(|resolved| (root.clone(), resolved)))
+            .collect()
+    } else {
+        Vec::new()
+    };
 
     if config.read_fdignore {
         walker.add_custom_ignore_filename(".fdignore");
+
+        for ignore_file_name in &config.ignore_file_names {
+            walker.add_custom_ignore_filename(ignore_file_name);
+        }
     }
 
     if config.read_global_ignore {
@@ -99,13 +279,13 @@ pub fn scan(path_vec: &[PathBuf], pattern: Arc<Regex>, config: Arc<Options>) ->
             let result = walker.add_ignore(global_ignore_file);
             match result {
                 Some(ignore::Error::Partial(_)) => (),
-                Some(err) => {
+                Some(err) if !config.quiet => {
                     print_error(format!(
                         "Malformed pattern in global ignore file. {}.",
                         err.to_string()
                     ));
                 }
-                None => (),
+                Some(_) | None => (),
             }
         }
     }
@@ -114,18 +294,18 @@ pub fn scan(path_vec: &[PathBuf], pattern: Arc<Regex>, config: Arc<Options>) ->
         let result = walker.add_ignore(ignore_file);
         match result {
             Some(ignore::Error::Partial(_)) => (),
-            Some(err) => {
+            Some(err) if !config.quiet => {
                 print_error(format!(
                     "Malformed pattern in custom ignore file. {}.",
                     err.to_string()
                 ));
             }
-            None => (),
+            Some(_) | None => (),
         }
     }
 
     for path_entry in path_iter {
-        walker.add(path_entry.as_path());
+        walker.add(filesystem::ensure_long_path_prefix(path_entry.clone()));
     }
 
     let parallel_walker = walker.threads(config.threads).build_parallel();
@@ -147,12 +327,54 @@ pub fn scan(path_vec: &[PathBuf], pattern: Arc<Regex>, config: Arc<Options>) ->
     // Spawn the thread that receives all results through the channel.
     let receiver_thread = spawn_receiver(&config, &wants_to_quit, rx);
 
+    // Spawn the thread that prints a periodically-updating '--progress' line to stderr, if
+    // requested. It shares the same 'stats' counters that '--stats' reports on at the end.
+    let progress_done = Arc::new(AtomicBool::new(false));
+    let progress_thread = config.progress.then(|| {
+        let stats = Arc::clone(stats.as_ref().expect("'--progress' implies 'stats' tracking"));
+        let done = Arc::clone(&progress_done);
+        thread::spawn(move || print_progress_until_done(&stats, &done))
+    });
+
     // Spawn the sender threads.
-    spawn_senders(&config, &wants_to_quit, pattern, parallel_walker, tx);
+    spawn_senders(
+        &config,
+        &wants_to_quit,
+        pattern,
+        parallel_walker,
+        tx,
+        stats.clone(),
+        cwd_components,
+        root_rewrites,
+    );
 
     // Wait for the receiver thread to print out all results.
     let exit_code = receiver_thread.join().unwrap();
 
+    // Stop and clear the progress line before any further output (e.g. the '--stats' summary
+    // below) is printed.
+    progress_done.store(true, Ordering::Relaxed);
+    if let Some(progress_thread) = progress_thread {
+        progress_thread.join().unwrap();
+    }
+
+    if let Some(stats) = stats {
+        eprintln!(
+            "{} matches found ({} files, {} directories, {} symlinks, {} other) in {} \
+             directories visited ({:.3}s)",
+            stats.matched_files.load(Ordering::Relaxed)
+                + stats.matched_dirs.load(Ordering::Relaxed)
+                + stats.matched_symlinks.load(Ordering::Relaxed)
+                + stats.matched_other.load(Ordering::Relaxed),
+            stats.matched_files.load(Ordering::Relaxed),
+            stats.matched_dirs.load(Ordering::Relaxed),
+            stats.matched_symlinks.load(Ordering::Relaxed),
+            stats.matched_other.load(Ordering::Relaxed),
+            stats.dirs_visited.load(Ordering::Relaxed),
+            stats_start_time.elapsed().as_secs_f64(),
+        );
+    }
+
     if wants_to_quit.load(Ordering::Relaxed) {
         Ok(ExitCode::KilledBySigint)
     } else {
@@ -168,7 +390,7 @@ fn spawn_receiver(
     let config = Arc::clone(config);
     let wants_to_quit = Arc::clone(wants_to_quit);
 
-    let show_filesystem_errors = config.show_filesystem_errors;
+    let show_filesystem_errors = config.show_filesystem_errors && !config.quiet;
     let threads = config.threads;
 
     thread::spawn(move || {
@@ -209,8 +431,13 @@ fn spawn_receiver(
 
             let mut buffer = vec![];
 
-            // Start in buffering mode
-            let mut mode = ReceiverMode::Buffering;
+            // Start in buffering mode, unless '--no-buffer' asks us to stream (and flush)
+            // every result immediately.
+            let mut mode = if config.no_buffer {
+                ReceiverMode::Streaming
+            } else {
+                ReceiverMode::Buffering
+            };
 
             // Maximum time to wait before we start streaming to the console.
             let max_buffer_time = config
@@ -218,9 +445,11 @@ fn spawn_receiver(
                 .unwrap_or_else(|| time::Duration::from_millis(100));
 
             let stdout = io::stdout();
-            let mut stdout = stdout.lock();
+            let mut stdout = io::BufWriter::new(stdout.lock());
 
             let mut num_results = 0;
+            let mut num_errors = 0;
+            let mut had_filesystem_errors = false;
 
             for worker_result in rx {
                 match worker_result {
@@ -230,17 +459,27 @@ fn spawn_receiver(
                                 buffer.push(value);
 
                                 // Have we reached the maximum buffer size or maximum buffering time?
-                                if buffer.len() > MAX_BUFFER_LENGTH
-                                    || time::Instant::now() - start > max_buffer_time
+                                // If the user requested sorted output, we always keep buffering
+                                // until every result has been collected.
+                                if config.sort_by.is_none()
+                                    && (buffer.len() > MAX_BUFFER_LENGTH
+                                        || time::Instant::now() - start >= max_buffer_time)
                                 {
+                                    sort_buffered_chunk_if_interactive(
+                                        &mut buffer,
+                                        config.interactive_terminal,
+                                    );
+
                                     // Flush the buffer
-                                    for v in &buffer {
-                                        output::print_entry(
-                                            &mut stdout,
-                                            v,
-                                            &config,
-                                            &wants_to_quit,
-                                        );
+                                    if !config.count {
+                                        for v in &buffer {
+                                            output::print_entry(
+                                                &mut stdout,
+                                                v,
+                                                &config,
+                                                &wants_to_quit,
+                                            );
+                                        }
                                     }
                                     buffer.clear();
 
@@ -249,15 +488,23 @@ fn spawn_receiver(
                                 }
                             }
                             ReceiverMode::Streaming => {
-                                output::print_entry(&mut stdout, &value, &config, &wants_to_quit);
+                                if !config.count {
+                                    output::print_entry(&mut stdout, &value, &config, &wants_to_quit);
+                                    if config.no_buffer {
+                                        let _ = stdout.flush();
+                                    }
+                                }
                             }
                         }
 
                         num_results += 1;
                     }
                     WorkerResult::Error(err) => {
+                        had_filesystem_errors = true;
                         if show_filesystem_errors {
                             print_error(err.to_string());
+                        } else {
+                            num_errors += 1;
                         }
                     }
                 }
@@ -272,13 +519,40 @@ fn spawn_receiver(
             // If we have finished fast enough (faster than max_buffer_time), we haven't streamed
             // anything to the console, yet. In this case, sort the results and print them:
             if !buffer.is_empty() {
-                buffer.sort();
-                for value in buffer {
-                    output::print_entry(&mut stdout, &value, &config, &wants_to_quit);
+                match config.sort_by {
+                    Some(sort_by) => sort_by_criterion(&mut buffer, sort_by, config.sort_reverse),
+                    None => buffer.sort(),
                 }
+                if !config.count {
+                    for value in buffer {
+                        output::print_entry(&mut stdout, &value, &config, &wants_to_quit);
+                    }
+                }
+            }
+
+            if config.count {
+                let _ = writeln!(stdout, "{}", num_results);
+            }
+
+            if num_errors > 0 && !config.quiet {
+                print_error(format!(
+                    "{} error(s) occurred while searching (use '--show-errors' for details)",
+                    num_errors
+                ));
+            }
+
+            if stdout.flush().is_err() {
+                // Probably a broken pipe. Exit gracefully.
+                process::exit(ExitCode::GeneralError.into());
             }
 
-            ExitCode::Success
+            if had_filesystem_errors {
+                ExitCode::GeneralError
+            } else if num_results == 0 {
+                ExitCode::NoMatchesFound
+            } else {
+                ExitCode::Success
+            }
         }
     })
 }
@@ -326,12 +600,17 @@ fn spawn_senders(
     pattern: Arc<Regex>,
     parallel_walker: ignore::WalkParallel,
     tx: Sender<WorkerResult>,
+    stats: Option<Arc<SearchStats>>,
+    cwd_components: Option<usize>,
+    root_rewrites: Vec<(PathBuf, PathBuf)>,
 ) {
     parallel_walker.run(|| {
         let config = Arc::clone(config);
         let pattern = Arc::clone(&pattern);
         let tx_thread = tx.clone();
         let wants_to_quit = Arc::clone(wants_to_quit);
+        let stats = stats.clone();
+        let root_rewrites = root_rewrites.clone();
 
         Box::new(move |entry_o| {
             if wants_to_quit.load(Ordering::Relaxed) {
@@ -340,10 +619,21 @@ fn spawn_senders(
 
             let entry = match entry_o {
                 Ok(ref e) if e.depth() == 0 => {
-                    // Skip the root directory entry.
+                    // The root entry itself is never printed, but with 'FollowMode::None' it
+                    // must not be descended into either, if it happens to be a symlink.
+                    if should_skip_symlink_root(config.follow, root_is_symlink(e.path())) {
+                        return ignore::WalkState::Skip;
+                    }
                     return ignore::WalkState::Continue;
                 }
-                Ok(e) => DirEntry::Normal(e),
+                Ok(e) => {
+                    if let Some(ref stats) = stats {
+                        if e.file_type().map_or(false, |ft| ft.is_dir()) {
+                            stats.dirs_visited.fetch_add(1, Ordering::Relaxed);
+                        }
+                    }
+                    DirEntry::Normal(e)
+                }
                 Err(ignore::Error::WithPath {
                     path,
                     err: inner_err,
@@ -381,42 +671,133 @@ fn spawn_senders(
                 },
             };
 
+            let depth = match cwd_components {
+                // '--depth-from cwd': re-derive the depth from the entry's absolute path,
+                // regardless of which search root it was found under.
+                Some(cwd_components) => filesystem::path_absolute_form(entry.path())
+                    .ok()
+                    .map(|p| p.components().count().saturating_sub(cwd_components)),
+                None => entry.depth(),
+            };
+
             if let Some(min_depth) = config.min_depth {
-                if entry.depth().map_or(true, |d| d < min_depth) {
+                if depth.map_or(true, |d| d < min_depth) {
                     return ignore::WalkState::Continue;
                 }
             }
 
-            // Check the name first, since it doesn't require metadata
-            let entry_path = entry.path();
+            if cwd_components.is_some() {
+                if let Some(max_depth) = config.max_depth {
+                    if depth.map_or(true, |d| d > max_depth) {
+                        return ignore::WalkState::Skip;
+                    }
+                }
+            }
 
-            let search_str: Cow<OsStr> = if config.search_full_path {
+            // Check the name first, since it doesn't require metadata. Normalize away the
+            // '\\?\' extended-length prefix added for the walk (Windows only; a no-op here
+            // otherwise), so matching and display see the path the user would expect.
+            let entry_path_normalized = filesystem::strip_long_path_prefix(entry.path());
+            let entry_path = entry_path_normalized.as_ref();
+
+            // Several filters below (file type, size, time, ownership, permissions) each need
+            // the entry's metadata; fetch it lazily, at most once per entry, instead of
+            // re-stat'ing for every filter that wants it. Always follows symlinks (consistent
+            // with `entry_path.metadata()`), regardless of whether the walker itself is
+            // following symlinks, so a broken symlink consistently fails every such filter.
+            let mut metadata = memoized(|| entry_path.metadata().ok());
+
+            let basename = match entry_path.file_name() {
+                Some(filename) => filename,
+                None => unreachable!(
+                    "Encountered file system entry without a file name. This should only \
+                     happen for paths like 'foo/bar/..' or '/' which are not supposed to \
+                     appear in a file system traversal."
+                ),
+            };
+
+            let full_path = || -> OsString {
                 let path_abs_buf = filesystem::path_absolute_form(entry_path)
                     .expect("Retrieving absolute path succeeds");
-                Cow::Owned(path_abs_buf.as_os_str().to_os_string())
-            } else {
-                match entry_path.file_name() {
-                    Some(filename) => Cow::Borrowed(filename),
-                    None => unreachable!(
-                        "Encountered file system entry without a file name. This should only \
-                         happen for paths like 'foo/bar/..' or '/' which are not supposed to \
-                         appear in a file system traversal."
-                    ),
+
+                // Match portably against forward-slash-separated paths, regardless of the
+                // platform's native separator, so patterns like 'src/.*\.rs$' work on Windows too.
+                #[cfg(windows)]
+                let path_abs_buf =
+                    PathBuf::from(path_abs_buf.to_string_lossy().replace('\\', "/"));
+
+                path_abs_buf.into_os_string()
+            };
+
+            let is_symlink = entry.file_type().map_or(false, |ft| ft.is_symlink());
+
+            // For '--follow-and-match-target', match a symlink entry against its resolved
+            // target path instead of its own name. `Path::metadata` follows the link, so it
+            // fails for a broken symlink, making such links never match under this mode.
+            let symlink_target = || -> Option<OsString> {
+                entry_path
+                    .metadata()
+                    .ok()
+                    .and_then(|_| fs::read_link(entry_path).ok())
+                    .map(PathBuf::into_os_string)
+            };
+
+            let matches_pattern = |pattern: &Regex| -> bool {
+                if config.match_symlink_target && is_symlink {
+                    symlink_target().map_or(false, |target| {
+                        pattern.is_match(&filesystem::osstr_to_bytes(&target))
+                    })
+                } else if config.search_full_path {
+                    pattern.is_match(&filesystem::osstr_to_bytes(&full_path()))
+                } else if config.search_full_path_or_name {
+                    pattern.is_match(&filesystem::osstr_to_bytes(basename))
+                        || pattern.is_match(&filesystem::osstr_to_bytes(&full_path()))
+                } else {
+                    pattern.is_match(&filesystem::osstr_to_bytes(basename))
                 }
             };
 
-            if !pattern.is_match(&filesystem::osstr_to_bytes(search_str.as_ref())) {
+            // The primary pattern and every '--and' pattern must all match, and no '--not'
+            // pattern may match.
+            let is_match = matches_pattern(&pattern)
+                && config.and_patterns.iter().all(|p| matches_pattern(p))
+                && !config.not_patterns.iter().any(|p| matches_pattern(p));
+
+            if !is_match {
                 return ignore::WalkState::Continue;
             }
 
+            // A directory matching the search pattern must not be descended into when
+            // `--prune` is set, even if it ends up being filtered out (e.g. by `--type f`)
+            // and therefore never printed.
+            let is_matched_dir =
+                config.prune && entry.file_type().map_or(false, |ft| ft.is_dir());
+            let continue_or_skip = || {
+                if is_matched_dir {
+                    ignore::WalkState::Skip
+                } else {
+                    ignore::WalkState::Continue
+                }
+            };
+
+            // Filter out entries whose full (relative) path matches an --exclude-regex pattern.
+            if let Some(ref exclude_regex) = config.exclude_regex {
+                if exclude_regex.is_match(&filesystem::osstr_to_bytes(entry_path.as_os_str())) {
+                    return continue_or_skip();
+                }
+            }
+
             // Filter out unwanted extensions.
             if let Some(ref exts_regex) = config.extensions {
                 if let Some(path_str) = entry_path.file_name() {
-                    if !exts_regex.is_match(&filesystem::osstr_to_bytes(path_str)) {
-                        return ignore::WalkState::Continue;
+                    let has_no_extension = entry_path.extension().is_none();
+                    if !exts_regex.is_match(&filesystem::osstr_to_bytes(path_str))
+                        && !(config.match_no_extension && has_no_extension)
+                    {
+                        return continue_or_skip();
                     }
                 } else {
-                    return ignore::WalkState::Continue;
+                    return continue_or_skip();
                 }
             }
 
@@ -428,63 +809,122 @@ fn spawn_senders(
                         || (!file_types.symlinks && entry_type.is_symlink())
                         || (!file_types.sockets && filesystem::is_socket(entry_type))
                         || (!file_types.pipes && filesystem::is_pipe(entry_type))
+                        || (!file_types.char_devices && filesystem::is_char_device(entry_type))
+                        || (!file_types.block_devices && filesystem::is_block_device(entry_type))
                         || (file_types.executables_only
-                            && !entry
-                                .metadata()
-                                .map(|m| filesystem::is_executable(&m))
+                            && !metadata()
+                                .map(|m| filesystem::is_executable(entry.path(), &m))
                                 .unwrap_or(false))
                         || (file_types.empty_only && !filesystem::is_empty(&entry))
                         || !(entry_type.is_file()
                             || entry_type.is_dir()
                             || entry_type.is_symlink()
                             || filesystem::is_socket(entry_type)
-                            || filesystem::is_pipe(entry_type))
+                            || filesystem::is_pipe(entry_type)
+                            || filesystem::is_char_device(entry_type)
+                            || filesystem::is_block_device(entry_type))
                     {
-                        return ignore::WalkState::Continue;
+                        return continue_or_skip();
                     }
                 } else {
-                    return ignore::WalkState::Continue;
+                    return continue_or_skip();
+                }
+            }
+
+            // Filter out file types excluded via '--type-not'.
+            if let Some(ref exclude_file_types) = config.exclude_file_types {
+                if let Some(ref entry_type) = entry.file_type() {
+                    if (exclude_file_types.files && entry_type.is_file())
+                        || (exclude_file_types.directories && entry_type.is_dir())
+                        || (exclude_file_types.symlinks && entry_type.is_symlink())
+                        || (exclude_file_types.sockets && filesystem::is_socket(entry_type))
+                        || (exclude_file_types.pipes && filesystem::is_pipe(entry_type))
+                        || (exclude_file_types.char_devices
+                            && filesystem::is_char_device(entry_type))
+                        || (exclude_file_types.block_devices
+                            && filesystem::is_block_device(entry_type))
+                        || (exclude_file_types.executables_only
+                            && metadata()
+                                .map(|m| filesystem::is_executable(entry.path(), &m))
+                                .unwrap_or(false))
+                        || (exclude_file_types.empty_only && filesystem::is_empty(&entry))
+                    {
+                        return continue_or_skip();
+                    }
                 }
             }
 
             #[cfg(unix)]
             {
                 if let Some(ref owner_constraint) = config.owner_constraint {
-                    if let Ok(ref metadata) = entry_path.metadata() {
-                        if !owner_constraint.matches(&metadata) {
-                            return ignore::WalkState::Continue;
+                    if let Some(ref md) = metadata() {
+                        if !owner_constraint.matches(md) {
+                            return continue_or_skip();
                         }
                     } else {
-                        return ignore::WalkState::Continue;
+                        return continue_or_skip();
+                    }
+                }
+
+                if let Some(ref permission_constraint) = config.permission_constraint {
+                    if let Some(ref md) = metadata() {
+                        if !permission_constraint.matches(md) {
+                            return continue_or_skip();
+                        }
+                    } else {
+                        return continue_or_skip();
+                    }
+                }
+
+                if let Some(ref same_file_filter) = config.same_file_filter {
+                    if let Some(ref md) = metadata() {
+                        if !same_file_filter.matches(md) {
+                            return continue_or_skip();
+                        }
+                    } else {
+                        return continue_or_skip();
                     }
                 }
             }
 
-            // Filter out unwanted sizes if it is a file and we have been given size constraints.
+            // Filter out unwanted sizes. The size filter always applies to regular files. It
+            // only applies to directories when '--type d' was explicitly given, so that
+            // '--size +1M --type d' can be used to find oversized directories; without
+            // '--type d', directories aren't subject to the size filter at all. Every other
+            // entry that isn't a regular file or a directory (e.g. a symlink) is dropped.
             if !config.size_constraints.is_empty() {
-                if entry_path.is_file() {
-                    if let Ok(metadata) = entry_path.metadata() {
-                        let file_size = metadata.len();
+                let want_directory_sizes = config
+                    .file_types
+                    .as_ref()
+                    .map_or(false, |file_types| file_types.directories);
+
+                let entry_type = entry.file_type();
+                let is_file = entry_type.map_or(false, |ft| ft.is_file());
+                let is_dir = entry_type.map_or(false, |ft| ft.is_dir());
+
+                if is_file || (want_directory_sizes && is_dir) {
+                    if let Some(md) = metadata() {
+                        let file_size = md.len();
                         if config
                             .size_constraints
                             .iter()
                             .any(|sc| !sc.is_within(file_size))
                         {
-                            return ignore::WalkState::Continue;
+                            return continue_or_skip();
                         }
                     } else {
-                        return ignore::WalkState::Continue;
+                        return continue_or_skip();
                     }
-                } else {
-                    return ignore::WalkState::Continue;
+                } else if !is_dir {
+                    return continue_or_skip();
                 }
             }
 
             // Filter out unwanted modification times
             if !config.time_constraints.is_empty() {
                 let mut matched = false;
-                if let Ok(metadata) = entry_path.metadata() {
-                    if let Ok(modified) = metadata.modified() {
+                if let Some(md) = metadata() {
+                    if let Ok(modified) = md.modified() {
                         matched = config
                             .time_constraints
                             .iter()
@@ -492,11 +932,33 @@ fn spawn_senders(
                     }
                 }
                 if !matched {
-                    return ignore::WalkState::Continue;
+                    return continue_or_skip();
                 }
             }
 
-            let send_result = tx_thread.send(WorkerResult::Entry(entry_path.to_owned()));
+            if let Some(ref stats) = stats {
+                let counter = match entry.file_type() {
+                    Some(ref ft) if ft.is_dir() => &stats.matched_dirs,
+                    Some(ref ft) if ft.is_symlink() => &stats.matched_symlinks,
+                    Some(ref ft) if ft.is_file() => &stats.matched_files,
+                    _ => &stats.matched_other,
+                };
+                counter.fetch_add(1, Ordering::Relaxed);
+            }
+
+            // Rewrite the search root portion of the path, if it matches one that should be
+            // displayed resolved, keeping the rest of the path (relative to that root) intact.
+            let display_path: Cow<Path> = root_rewrites
+                .iter()
+                .find_map(|(root, resolved)| {
+                    entry_path
+                        .strip_prefix(root)
+                        .ok()
+                        .map(|rest| Cow::Owned(resolved.join(rest)))
+                })
+                .unwrap_or(Cow::Borrowed(entry_path));
+
+            let send_result = tx_thread.send(WorkerResult::Entry(display_path.into_owned()));
 
             if send_result.is_err() {
                 return ignore::WalkState::Quit;
@@ -511,3 +973,54 @@ fn spawn_senders(
         })
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sort_buffered_chunk_if_interactive_only_sorts_for_interactive_terminals() {
+        let mut buffer = vec![PathBuf::from("b"), PathBuf::from("a")];
+
+        sort_buffered_chunk_if_interactive(&mut buffer, false);
+        assert_eq!(buffer, vec![PathBuf::from("b"), PathBuf::from("a")]);
+
+        sort_buffered_chunk_if_interactive(&mut buffer, true);
+        assert_eq!(buffer, vec![PathBuf::from("a"), PathBuf::from("b")]);
+    }
+
+    #[test]
+    fn memoized_calls_the_function_at_most_once() {
+        let calls = std::cell::Cell::new(0);
+        let mut cached = memoized(|| {
+            calls.set(calls.get() + 1);
+            Some(42)
+        });
+
+        assert_eq!(cached(), Some(42));
+        assert_eq!(cached(), Some(42));
+        assert_eq!(cached(), Some(42));
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn should_skip_symlink_root_only_for_follow_mode_none() {
+        assert!(should_skip_symlink_root(FollowMode::None, true));
+        assert!(!should_skip_symlink_root(FollowMode::None, false));
+        assert!(!should_skip_symlink_root(FollowMode::RootsOnly, true));
+        assert!(!should_skip_symlink_root(FollowMode::All, true));
+    }
+
+    #[test]
+    fn expand_brace_pattern_expands_comma_separated_alternatives() {
+        assert_eq!(
+            expand_brace_pattern("*.{tmp,bak}"),
+            vec!["*.tmp".to_owned(), "*.bak".to_owned()]
+        );
+    }
+
+    #[test]
+    fn expand_brace_pattern_leaves_patterns_without_braces_unchanged() {
+        assert_eq!(expand_brace_pattern("*.pyc"), vec!["*.pyc".to_owned()]);
+    }
+}
@@ -20,6 +20,12 @@ pub struct TestEnv {
 
     /// Normalize each line by sorting the whitespace-separated words
     normalize_line: bool,
+
+    /// Extra environment variables to set for the *fd* process.
+    envs: Vec<(String, String)>,
+
+    /// Environment variables to unset for the *fd* process, overriding the parent's.
+    envs_removed: Vec<String>,
 }
 
 /// Create the working directory and the test files.
@@ -139,6 +145,8 @@ impl TestEnv {
             temp_dir,
             fd_exe,
             normalize_line: false,
+            envs: Vec::new(),
+            envs_removed: Vec::new(),
         }
     }
 
@@ -147,9 +155,23 @@ impl TestEnv {
             temp_dir: self.temp_dir,
             fd_exe: self.fd_exe,
             normalize_line: normalize,
+            envs: self.envs,
+            envs_removed: self.envs_removed,
         }
     }
 
+    /// Set an environment variable for the *fd* process, e.g. to override `LS_COLORS`.
+    pub fn env<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> TestEnv {
+        self.envs.push((key.into(), value.into()));
+        self
+    }
+
+    /// Unset an environment variable for the *fd* process, overriding the parent's.
+    pub fn env_remove<K: Into<String>>(mut self, key: K) -> TestEnv {
+        self.envs_removed.push(key.into());
+        self
+    }
+
     /// Create a broken symlink at the given path in the temp_dir.
     pub fn create_broken_symlink<P: AsRef<Path>>(
         &mut self,
@@ -169,6 +191,20 @@ impl TestEnv {
         Ok(broken_symlink_link)
     }
 
+    /// Create a symlink at `link_path` pointing at `target_path`, both relative to the temp_dir.
+    pub fn create_symlink<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        target_path: P,
+        link_path: Q,
+    ) -> Result<(), io::Error> {
+        let root = self.test_root();
+        #[cfg(unix)]
+        unix::fs::symlink(root.join(target_path), root.join(link_path))?;
+        #[cfg(windows)]
+        windows::fs::symlink_dir(root.join(target_path), root.join(link_path))?;
+        Ok(())
+    }
+
     /// Get the root directory for the tests.
     pub fn test_root(&self) -> PathBuf {
         self.temp_dir.path().to_path_buf()
@@ -191,6 +227,10 @@ impl TestEnv {
         let mut cmd = process::Command::new(&self.fd_exe);
         cmd.current_dir(self.temp_dir.path().join(path));
         cmd.arg("--no-global-ignore-file").args(args);
+        cmd.envs(self.envs.iter().map(|(k, v)| (k.as_str(), v.as_str())));
+        for key in &self.envs_removed {
+            cmd.env_remove(key);
+        }
 
         // Run *fd*.
         let output = cmd.output().expect("fd output");
@@ -257,6 +297,60 @@ impl TestEnv {
         }
     }
 
+    /// Assert that calling *fd* with the specified arguments does not succeed, and return the
+    /// full output (including stderr) for further inspection.
+    pub fn assert_failure_and_get_output(&self, args: &[&str]) -> process::Output {
+        let mut cmd = process::Command::new(&self.fd_exe);
+        cmd.current_dir(self.temp_dir.path());
+        cmd.arg("--no-global-ignore-file").args(args);
+
+        let output = cmd.output().expect("fd output");
+        if output.status.success() {
+            panic!("Failure did not occur as expected.");
+        }
+
+        output
+    }
+
+    /// Assert that calling *fd* with the specified arguments produces no output and does not
+    /// succeed. Any non-zero status is accepted, the "no matches found" status as well as an
+    /// error status.
+    pub fn assert_no_matches(&self, args: &[&str]) {
+        let mut cmd = process::Command::new(&self.fd_exe);
+        cmd.current_dir(self.temp_dir.path());
+        cmd.arg("--no-global-ignore-file").args(args);
+
+        let output = cmd.output().expect("fd output");
+
+        if output.status.success() || !output.stdout.is_empty() {
+            panic!(format_exit_error(args, &output));
+        }
+    }
+
+    /// Assert that calling *fd* with the specified arguments, with its stdout closed before it
+    /// has finished writing (as happens when piping into a process that exits early, e.g.
+    /// `head`), exits without printing a Rust panic message to stderr.
+    pub fn assert_no_panic_on_closed_stdout(&self, args: &[&str]) {
+        let mut cmd = process::Command::new(&self.fd_exe);
+        cmd.current_dir(self.temp_dir.path());
+        cmd.arg("--no-global-ignore-file").args(args);
+        cmd.stdout(process::Stdio::piped());
+        cmd.stderr(process::Stdio::piped());
+
+        let mut child = cmd.spawn().expect("fd spawn");
+
+        // Drop the read end of the pipe right away, so that *fd*'s next write fails with a
+        // broken pipe error.
+        drop(child.stdout.take());
+
+        let output = child.wait_with_output().expect("fd output");
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        if stderr.contains("panicked at") {
+            panic!("fd panicked instead of exiting gracefully:\n{}", stderr);
+        }
+    }
+
     /// Assert that calling *fd* with the specified arguments produces the expected error.
     pub fn assert_error(&self, args: &[&str], expected: &str) -> process::ExitStatus {
         self.assert_error_subdirectory(".", args, Some(expected))
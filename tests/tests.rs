@@ -72,6 +72,32 @@ fn test_simple() {
     );
 }
 
+/// `--pattern-file` reads the search pattern from a file instead of a positional argument,
+/// stripping a trailing newline.
+#[test]
+fn test_pattern_file() {
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
+
+    let pattern_file = te.test_root().join("pattern.txt");
+    fs::write(&pattern_file, "d.foo\n").unwrap();
+
+    te.assert_output(
+        &["--pattern-file", pattern_file.to_str().unwrap()],
+        "one/two/three/d.foo",
+    );
+}
+
+/// `--pattern-file` and a positional pattern argument are mutually exclusive.
+#[test]
+fn test_pattern_file_conflicts_with_pattern() {
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
+
+    let pattern_file = te.test_root().join("pattern.txt");
+    fs::write(&pattern_file, "d.foo\n").unwrap();
+
+    te.assert_failure(&["--pattern-file", pattern_file.to_str().unwrap(), "foo"]);
+}
+
 /// Test each pattern type with an empty pattern.
 #[test]
 fn test_empty_pattern() {
@@ -251,11 +277,16 @@ fn test_case_sensitive() {
     te.assert_output(&["--case-sensitive", "c.foo"], "one/two/c.foo");
 
     te.assert_output(&["--case-sensitive", "C.Foo"], "one/two/C.Foo2");
+}
 
-    te.assert_output(
-        &["--ignore-case", "--case-sensitive", "C.Foo"],
-        "one/two/C.Foo2",
-    );
+/// `--ignore-case` and `--case-sensitive` are mutually exclusive; combining them is an error
+/// instead of silently letting one win.
+#[test]
+fn test_case_sensitive_conflicts_with_ignore_case() {
+    let te = TestEnv::new(&[], &[]);
+
+    te.assert_failure(&["--ignore-case", "--case-sensitive", "foo"]);
+    te.assert_failure(&["--case-sensitive", "--ignore-case", "foo"]);
 }
 
 /// Case insensitivity (--ignore-case)
@@ -268,12 +299,6 @@ fn test_case_insensitive() {
         "one/two/c.foo
         one/two/C.Foo2",
     );
-
-    te.assert_output(
-        &["--case-sensitive", "--ignore-case", "C.Foo"],
-        "one/two/c.foo
-        one/two/C.Foo2",
-    );
 }
 
 /// Glob-based searches (--glob)
@@ -305,6 +330,21 @@ fn test_glob_searches() {
     );
 }
 
+/// Glob-based searches (--glob) against a specific file extension, as one would use when
+/// migrating a `find -name '*.ext'` invocation.
+#[test]
+fn test_glob_searches_by_extension() {
+    let dirs = &["src", "src/bin"];
+    let files = &["src/main.rs", "src/bin/fd.rs", "README.md", "Cargo.toml"];
+    let te = TestEnv::new(dirs, files);
+
+    te.assert_output(
+        &["--glob", "*.rs"],
+        "src/main.rs
+        src/bin/fd.rs",
+    );
+}
+
 /// Glob-based searches (--glob) in combination with full path searches (--full-path)
 #[cfg(not(windows))] // TODO: make this work on Windows
 #[test]
@@ -329,6 +369,36 @@ fn test_full_path_glob_searches() {
     );
 }
 
+/// '--literal-separator' makes explicit that '*' does not cross the path separator in glob
+/// searches, which is already fd's default behavior. '--no-literal-separator' is the only way
+/// to turn that off, letting '*' match across directories.
+#[cfg(not(windows))] // TODO: make this work on Windows
+#[test]
+fn test_literal_separator_glob_searches() {
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
+
+    te.assert_output(
+        &[
+            "--glob",
+            "--literal-separator",
+            "--full-path",
+            "**/one/*/*.foo",
+        ],
+        " one/two/c.foo",
+    );
+
+    te.assert_output(
+        &[
+            "--glob",
+            "--no-literal-separator",
+            "--full-path",
+            "**/one/*/*.foo",
+        ],
+        " one/two/c.foo
+          one/two/three/d.foo",
+    );
+}
+
 #[test]
 fn test_smart_case_glob_searches() {
     let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
@@ -387,6 +457,53 @@ fn test_full_path() {
     );
 }
 
+/// An inline regex flag like `(?-i)` overrides `--ignore-case` for the scope it applies to,
+/// since the `regex` crate lets inline flags take precedence over the builder-level default.
+#[test]
+fn test_inline_regex_flag_overrides_ignore_case() {
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
+
+    // Without the inline flag, --ignore-case matches both 'c.foo' and 'C.Foo2'.
+    te.assert_output(
+        &["--ignore-case", "Foo"],
+        "a.foo
+        one/b.foo
+        one/two/c.foo
+        one/two/C.Foo2
+        one/two/three/d.foo
+        one/two/three/directory_foo",
+    );
+
+    // '(?-i)' re-enables case sensitivity for the rest of the pattern, even under --ignore-case.
+    te.assert_output(&["--ignore-case", "(?-i)C.Foo"], "one/two/C.Foo2");
+}
+
+/// `--full-path` matches the regex against the path rendered with `/` separators, so patterns
+/// written with a forward slash are portable across platforms.
+#[test]
+fn test_full_path_forward_slash_separator() {
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
+
+    te.assert_output(&["--full-path", r"two/c\.foo$"], "one/two/c.foo");
+}
+
+/// `--full-path-or-name` matches either the basename or somewhere in the full path, unlike the
+/// default (basename only) and `--full-path` (whole path must match).
+#[test]
+fn test_full_path_or_name() {
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
+
+    // A basename never contains a path separator, so a pattern requiring one can only match via
+    // the full path, never the basename alone, which makes this a clean way to distinguish modes.
+    te.assert_no_matches(&[r"two/c\.foo$"]);
+    te.assert_output(&["--full-path-or-name", r"two/c\.foo$"], "one/two/c.foo");
+
+    // A pattern that matches a basename still matches under '--full-path-or-name'.
+    te.assert_output(&["--full-path-or-name", "^a.foo$"], "a.foo");
+
+    te.assert_failure(&["--full-path-or-name", "--full-path", "foo"]);
+}
+
 /// Hidden files (--hidden)
 #[test]
 fn test_hidden() {
@@ -404,6 +521,76 @@ fn test_hidden() {
     );
 }
 
+/// `--no-hidden` takes precedence over an earlier `--hidden` on the same command line, so hidden
+/// files stay filtered out.
+#[test]
+fn test_no_hidden_overrides_hidden() {
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
+
+    te.assert_output(
+        &["--hidden", "--no-hidden", "foo"],
+        "a.foo
+        one/b.foo
+        one/two/c.foo
+        one/two/C.Foo2
+        one/two/three/d.foo
+        one/two/three/directory_foo",
+    );
+}
+
+/// `--hidden` and `--no-ignore` are independent: every combination of the two flags should
+/// behave as if the other one weren't involved.
+#[test]
+fn test_hidden_and_no_ignore_are_independent() {
+    let te = TestEnv::new(&[], &[]);
+
+    fs::File::create(te.test_root().join(".gitignore"))
+        .unwrap()
+        .write_all(b".hidden_and_ignored.foo")
+        .unwrap();
+    fs::File::create(te.test_root().join(".hidden_and_ignored.foo")).unwrap();
+    fs::File::create(te.test_root().join("visible.foo")).unwrap();
+
+    // Neither flag: only the visible, non-ignored file shows up.
+    te.assert_output(&["foo"], "visible.foo");
+
+    // --hidden only: the hidden file is found, but it's still excluded by .gitignore.
+    te.assert_output(&["--hidden", "foo"], "visible.foo");
+
+    // --no-ignore only: .gitignore is not respected, but hidden files are still skipped.
+    te.assert_output(&["--no-ignore", "foo"], "visible.foo");
+
+    // Both flags: the hidden, gitignored file is found too.
+    te.assert_output(
+        &["--hidden", "--no-ignore", "foo"],
+        ".hidden_and_ignored.foo
+        visible.foo",
+    );
+}
+
+/// `-u` is an alias for `--no-ignore`, and stacking it as `-uu` additionally implies `--hidden`.
+#[test]
+fn test_rg_alias_hidden_ignore() {
+    let te = TestEnv::new(&[], &[]);
+
+    fs::File::create(te.test_root().join(".gitignore"))
+        .unwrap()
+        .write_all(b".hidden_and_ignored.foo")
+        .unwrap();
+    fs::File::create(te.test_root().join(".hidden_and_ignored.foo")).unwrap();
+    fs::File::create(te.test_root().join("visible.foo")).unwrap();
+
+    // '-u' disables ignore files, but hidden files are still skipped.
+    te.assert_output(&["-u", "foo"], "visible.foo");
+
+    // '-uu' additionally shows hidden files, matching '--hidden --no-ignore'.
+    te.assert_output(
+        &["-uu", "foo"],
+        ".hidden_and_ignored.foo
+        visible.foo",
+    );
+}
+
 /// Hidden file attribute on Windows
 #[cfg(windows)]
 #[test]
@@ -423,7 +610,30 @@ fn test_hidden_file_attribute() {
         .unwrap();
 
     te.assert_output(&["--hidden", "hidden-file.txt"], "hidden-file.txt");
-    te.assert_output(&["hidden-file.txt"], "");
+    te.assert_no_matches(&["hidden-file.txt"]);
+}
+
+/// Deeply nested trees whose full path exceeds Windows' legacy 260-character `MAX_PATH` limit
+/// should still be searchable, thanks to the internal `\\?\` extended-length path handling.
+#[cfg(windows)]
+#[test]
+fn test_long_path() {
+    let te = TestEnv::new(&[], &[]);
+
+    let mut deep_dir = te.test_root();
+    let mut relative_components = Vec::new();
+    for i in 0..30 {
+        let component = format!("deeply_nested_directory_{}", i);
+        deep_dir.push(&component);
+        relative_components.push(component);
+    }
+    fs::create_dir_all(&deep_dir).expect("Failed to create deeply nested directory tree.");
+    fs::File::create(deep_dir.join("needle.txt")).expect("Failed to create file.");
+
+    assert!(deep_dir.to_string_lossy().len() > 260);
+
+    relative_components.push("needle.txt".to_string());
+    te.assert_output(&["needle.txt"], &relative_components.join("/"));
 }
 
 /// Ignored files (--no-ignore)
@@ -495,6 +705,47 @@ fn test_gitignore_and_fdignore() {
     );
 }
 
+/// `--ignore-file-name` registers an additional custom ignore-filename, on top of the built-in
+/// `.gitignore`, `.fdignore` and `.ignore`.
+#[test]
+fn test_ignore_file_name() {
+    let files = &["ignored-by-nothing", "ignored-by-rgignore"];
+    let te = TestEnv::new(&[], files);
+
+    fs::File::create(te.test_root().join(".rgignore"))
+        .unwrap()
+        .write_all(b"ignored-by-rgignore")
+        .unwrap();
+
+    // Without the flag, '.rgignore' isn't a filename fd knows to look for.
+    te.assert_output(
+        &["ignored"],
+        "ignored-by-nothing
+        ignored-by-rgignore",
+    );
+
+    te.assert_output(
+        &["--ignore-file-name", ".rgignore", "ignored"],
+        "ignored-by-nothing",
+    );
+}
+
+/// `.fdignore` rules nest per-directory, just like `.gitignore`.
+#[test]
+fn test_fdignore_nested_subtree() {
+    let dirs = &["secret", "public"];
+    let files = &["secret/data.txt", "public/data.txt"];
+    let te = TestEnv::new(dirs, files);
+
+    fs::File::create(te.test_root().join(".fdignore"))
+        .unwrap()
+        .write_all(b"secret/")
+        .unwrap();
+
+    te.assert_output(&["data.txt"], "public/data.txt");
+    te.assert_output(&["--no-ignore", "data.txt"], "public/data.txt\nsecret/data.txt");
+}
+
 /// Precedence of .fdignore files
 #[test]
 fn test_custom_ignore_precedence() {
@@ -538,6 +789,97 @@ fn test_no_ignore_vcs() {
     );
 }
 
+/// `--no-ignore-dot` disregards '.ignore'/'.fdignore' files, while still respecting '.gitignore'.
+#[test]
+fn test_no_ignore_dot() {
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
+
+    te.assert_output(
+        &["--no-ignore-dot", "foo"],
+        "a.foo
+        fdignored.foo
+        one/b.foo
+        one/two/c.foo
+        one/two/C.Foo2
+        one/two/three/d.foo
+        one/two/three/directory_foo",
+    );
+}
+
+/// `--no-ignore-parent` disregards ignore files found above the search root, while still
+/// respecting ones inside it.
+#[test]
+fn test_no_ignore_parent() {
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
+
+    fs::File::create(te.test_root().join(".gitignore"))
+        .unwrap()
+        .write_all(b"b.foo")
+        .unwrap();
+
+    // By default, the parent '.gitignore' above the 'one' search root is still respected.
+    te.assert_output_subdirectory("one", &[], "two
+        two/c.foo
+        two/C.Foo2
+        two/three
+        two/three/d.foo
+        two/three/directory_foo");
+
+    // '--no-ignore-parent' disregards it, so 'b.foo' shows up again.
+    te.assert_output_subdirectory(
+        "one",
+        &["--no-ignore-parent"],
+        "b.foo
+        two
+        two/c.foo
+        two/C.Foo2
+        two/three
+        two/three/d.foo
+        two/three/directory_foo",
+    );
+}
+
+/// Git's global gitignore file (--no-global-ignore-vcs)
+#[test]
+fn test_global_gitignore() {
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
+
+    // Fake a global gitignore file, resolved via $XDG_CONFIG_HOME/git/ignore.
+    let xdg_config_home = tempdir::TempDir::new("fd-tests-xdg-config-home").unwrap();
+    fs::create_dir_all(xdg_config_home.path().join("git")).unwrap();
+    fs::File::create(xdg_config_home.path().join("git/ignore"))
+        .unwrap()
+        .write_all(b"a.foo")
+        .unwrap();
+
+    let te = te.env(
+        "XDG_CONFIG_HOME",
+        xdg_config_home.path().to_str().unwrap().to_owned(),
+    );
+
+    // The global gitignore is respected by default, local .gitignore still applies.
+    te.assert_output(
+        &["foo"],
+        "one/b.foo
+        one/two/c.foo
+        one/two/C.Foo2
+        one/two/three/d.foo
+        one/two/three/directory_foo",
+    );
+
+    // '--no-global-ignore-vcs' disables just the global gitignore, while 'gitignored.foo' is
+    // still excluded by the local .gitignore.
+    te.assert_output(
+        &["--no-global-ignore-vcs", "foo"],
+        "a.foo
+        one/b.foo
+        one/two/c.foo
+        one/two/C.Foo2
+        one/two/three/d.foo
+        one/two/three/directory_foo",
+    );
+}
+
 /// Custom ignore files (--ignore-file)
 #[test]
 fn test_custom_ignore_files() {
@@ -555,6 +897,13 @@ fn test_custom_ignore_files() {
         one/b.foo
         one/two/c.foo",
     );
+
+    // --exclude patterns apply on top of a custom ignore file.
+    te.assert_output(
+        &["--ignore-file", "custom.ignore", "--exclude", "b.foo", "foo"],
+        "a.foo
+        one/two/c.foo",
+    );
 }
 
 /// Ignored files with ripgrep aliases (-u / -uu)
@@ -632,17 +981,23 @@ fn test_file_system_boundaries() {
         &["--full-path", "--max-depth", "2", "^/dev/null$", "/"],
         "/dev/null",
     );
-    te.assert_output(
-        &[
-            "--one-file-system",
-            "--full-path",
-            "--max-depth",
-            "2",
-            "^/dev/null$",
-            "/",
-        ],
-        "",
-    );
+    te.assert_no_matches(&[
+        "--one-file-system",
+        "--full-path",
+        "--max-depth",
+        "2",
+        "^/dev/null$",
+        "/",
+    ]);
+}
+
+/// `--mount` and `--xdev` are aliases for `--one-file-system`.
+#[test]
+#[cfg(unix)]
+fn test_one_file_system_aliases() {
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
+    te.assert_output(&["--mount", "a.foo"], "a.foo");
+    te.assert_output(&["--xdev", "a.foo"], "a.foo");
 }
 
 #[test]
@@ -662,16 +1017,16 @@ fn test_follow_broken_symlink() {
         symlink",
     );
 
-    te.assert_output(&["--type", "file", "symlink"], "");
+    te.assert_no_matches(&["--type", "file", "symlink"]);
 
     te.assert_output(
         &["--follow", "--type", "symlink", "symlink"],
         "broken_symlink",
     );
-    te.assert_output(&["--follow", "--type", "file", "symlink"], "");
+    te.assert_no_matches(&["--follow", "--type", "file", "symlink"]);
 }
 
-/// Null separator (--print0)
+/// Null separator (--print0 / -0)
 #[test]
 fn test_print0() {
     let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
@@ -685,6 +1040,24 @@ fn test_print0() {
         one/two/three/d.fooNULL
         one/two/three/directory_fooNULL",
     );
+
+    // '-0' is a short alias for '--print0'.
+    te.assert_output(
+        &["-0", "foo"],
+        "a.fooNULL
+        one/b.fooNULL
+        one/two/C.Foo2NULL
+        one/two/c.fooNULL
+        one/two/three/d.fooNULL
+        one/two/three/directory_fooNULL",
+    );
+
+    // '-0' coexists with '--exec': the command still receives the plain (non-null-terminated)
+    // path as its argument.
+    te.assert_output(
+        &["-0", "a.foo", "--exec", "echo", "{}"],
+        "a.foo",
+    );
 }
 
 /// Maximum depth (--max-depth)
@@ -724,25 +1097,136 @@ fn test_max_depth() {
     );
 }
 
-/// Minimum depth (--min-depth)
+/// Single-level listing (--flat/--no-recurse), equivalent to --max-depth=1
 #[test]
-fn test_min_depth() {
-    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
-
-    te.assert_output(
-        &["--min-depth", "3"],
-        "one/two/c.foo
-        one/two/C.Foo2
-        one/two/three
-        one/two/three/d.foo
-        one/two/three/directory_foo",
-    );
+fn test_flat() {
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES).normalize_line(true);
+
+    te.assert_output(
+        &["--flat"],
+        "a.foo
+        e1 e2
+        one
+        symlink",
+    );
+
+    // '--no-recurse' is an alias for '--flat'.
+    te.assert_output(
+        &["--no-recurse"],
+        "a.foo
+        e1 e2
+        one
+        symlink",
+    );
+
+    // Combines naturally with other filters, such as --type.
+    te.assert_output(&["--flat", "--type", "directory"], "one");
+
+    te.assert_failure_with_error(
+        &["--flat", "--max-depth", "2"],
+        "error: The argument '--max-depth <depth>' cannot be used with '--flat'",
+    );
+}
+
+/// Depth reference point for --max-depth (--depth-from)
+#[test]
+fn test_depth_from_cwd() {
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
+
+    // By default, depth is counted from each search root independently, so the deeper root
+    // ('one/two/three') still contributes its own direct children.
+    te.assert_output(
+        &[
+            "--max-depth",
+            "1",
+            "--search-path",
+            ".",
+            "--search-path",
+            "one/two/three",
+        ],
+        "./a.foo
+        ./e1 e2
+        ./one
+        ./symlink
+        one/two/three/d.foo
+        one/two/three/directory_foo",
+    );
+
+    // With '--depth-from cwd', depth is counted from the current working directory instead, so
+    // the deeper root's children (at cwd-depth 4) no longer satisfy '--max-depth 1'.
+    te.assert_output(
+        &[
+            "--max-depth",
+            "1",
+            "--depth-from",
+            "cwd",
+            "--search-path",
+            ".",
+            "--search-path",
+            "one/two/three",
+        ],
+        "./a.foo
+        ./e1 e2
+        ./one
+        ./symlink",
+    );
+}
+
+/// `--search-path` can be repeated, and combined with a positional <path> argument; all of the
+/// given roots are searched together.
+#[test]
+fn test_search_path_merges_with_positional_path() {
+    let dirs = &["test1", "test2", "test3"];
+    let files = &["test1/a.foo", "test2/a.foo", "test3/a.foo"];
+    let te = TestEnv::new(dirs, files);
+
+    te.assert_output(
+        &["--search-path", "test1", "a.foo", "test2"],
+        "test1/a.foo
+        test2/a.foo",
+    );
+
+    te.assert_output(
+        &[
+            "--search-path",
+            "test1",
+            "--search-path",
+            "test2",
+            "a.foo",
+            "test3",
+        ],
+        "test1/a.foo
+        test2/a.foo
+        test3/a.foo",
+    );
+}
+
+/// Minimum depth (--min-depth)
+#[test]
+fn test_min_depth() {
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
+
+    te.assert_output(
+        &["--min-depth", "3"],
+        "one/two/c.foo
+        one/two/C.Foo2
+        one/two/three
+        one/two/three/d.foo
+        one/two/three/directory_foo",
+    );
 
     te.assert_output(
         &["--min-depth", "4"],
         "one/two/three/d.foo
         one/two/three/directory_foo",
     );
+
+    // Combined with --max-depth, this pins an exact depth range.
+    te.assert_output(
+        &["--min-depth", "2", "--max-depth", "2"],
+        "one/b.foo
+        one/two",
+    );
 }
 
 /// Exact depth (--exact-depth)
@@ -756,6 +1240,19 @@ fn test_exact_depth() {
         one/two/C.Foo2
         one/two/three",
     );
+
+    // Only the direct children of the search root should be returned.
+    te.assert_output(
+        &["--exact-depth", "1"],
+        "a.foo
+        e1 e2
+        one
+        symlink",
+    );
+
+    // --exact-depth conflicts with --min-depth/--max-depth.
+    te.assert_failure(&["--exact-depth", "1", "--min-depth", "2"]);
+    te.assert_failure(&["--exact-depth", "1", "--max-depth", "2"]);
 }
 
 /// Pruning (--prune)
@@ -790,6 +1287,24 @@ fn test_prune() {
         bar/foo.file
         baz/foo.file",
     );
+
+    // --prune with --type f: the matched directory isn't printed (wrong type), but its
+    // children must still not be descended into.
+    te.assert_output(
+        &["--prune", "--type", "f", "foo"],
+        "bar/foo.file
+        baz/foo.file",
+    );
+
+    // --prune composes with --max-depth: a matched directory at the max depth is still
+    // printed and still pruned, regardless of how much depth budget would otherwise remain.
+    te.assert_output(
+        &["--prune", "--max-depth", "2", "foo"],
+        "foo
+        bar/foo
+        bar/foo.file
+        baz/foo.file",
+    );
 }
 
 /// Absolute paths (--absolute-path)
@@ -829,6 +1344,28 @@ fn test_absolute_path() {
     );
 }
 
+/// Resolve symlinks with --canonicalize
+#[test]
+#[cfg(unix)]
+fn test_canonicalize() {
+    let (te, abs_path) = get_test_env_with_abs_path(DEFAULT_DIRS, DEFAULT_FILES);
+
+    // 'symlink' points to 'one/two'; canonicalizing it should resolve to the real path.
+    te.assert_output(
+        &["^symlink$", "--canonicalize"],
+        &format!("{abs_path}/one/two", abs_path = &abs_path),
+    );
+
+    // A broken symlink falls back to its absolute (non-canonical) path.
+    let mut te = te;
+    te.create_broken_symlink("broken_symlink")
+        .expect("Failed to create broken symlink.");
+    let output =
+        te.assert_success_and_get_output(".", &["^broken_symlink$", "--canonicalize"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.trim().ends_with("broken_symlink"));
+}
+
 /// Show absolute paths if the path argument is absolute
 #[test]
 fn test_implicit_absolute_path() {
@@ -905,6 +1442,48 @@ fn test_type() {
     te.assert_output(&["--type", "l"], "symlink");
 }
 
+/// `--type-not` excludes a type from the results, complementing `--type`.
+#[test]
+fn test_type_not() {
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
+
+    te.assert_output(
+        &["--type-not", "l"],
+        "a.foo
+        e1 e2
+        one
+        one/b.foo
+        one/two
+        one/two/c.foo
+        one/two/C.Foo2
+        one/two/three
+        one/two/three/d.foo
+        one/two/three/directory_foo",
+    );
+
+    te.assert_output(
+        &["--type", "d", "--type-not", "d", "--type", "l"],
+        "symlink",
+    );
+}
+
+/// `--type-not empty` excludes only empty files/directories, unlike `--type empty`, it must not
+/// broaden to "exclude every file and directory" the way a bare `--type empty` broadens to
+/// "search files and directories".
+#[test]
+fn test_type_not_empty() {
+    let te = TestEnv::new(&["dir_empty"], &[]);
+
+    create_file_with_size(te.test_root().join("0_bytes.foo"), 0);
+    create_file_with_size(te.test_root().join("5_bytes.foo"), 5);
+
+    te.assert_output(
+        &["--type-not", "empty"],
+        "5_bytes.foo
+        symlink",
+    );
+}
+
 /// Test `--type executable`
 #[cfg(unix)]
 #[test]
@@ -932,16 +1511,44 @@ fn test_type_executable() {
     );
 }
 
+/// Test `--type pipe` and `--type char-device`/`--type block-device`
+#[cfg(unix)]
+#[test]
+fn test_type_special_files() {
+    use std::ffi::CString;
+
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
+
+    let fifo_path = te.test_root().join("my-fifo");
+    let fifo_path_c = CString::new(fifo_path.to_str().unwrap()).unwrap();
+    assert_eq!(0, unsafe { libc::mkfifo(fifo_path_c.as_ptr(), 0o644) });
+
+    te.assert_output(&["--type", "pipe"], "my-fifo");
+    te.assert_output(&["--type", "p"], "my-fifo");
+
+    // There's no portable way to create char/block devices without root, but we can at least
+    // confirm that a regular file never matches either filter.
+    te.assert_no_matches(&["--type", "char-device", "a.foo"]);
+    te.assert_no_matches(&["--type", "block-device", "a.foo"]);
+}
+
 /// Test `--type empty`
 #[test]
 fn test_type_empty() {
-    let te = TestEnv::new(&["dir_empty", "dir_nonempty"], &[]);
+    let te = TestEnv::new(&["dir_empty", "dir_nonempty", "dir_hidden_only"], &[]);
 
     create_file_with_size(te.test_root().join("0_bytes.foo"), 0);
     create_file_with_size(te.test_root().join("5_bytes.foo"), 5);
 
     create_file_with_size(te.test_root().join("dir_nonempty").join("2_bytes.foo"), 2);
 
+    // A directory that only contains a hidden file is not considered empty, regardless of
+    // whether `--hidden` is passed.
+    create_file_with_size(
+        te.test_root().join("dir_hidden_only").join(".hidden.foo"),
+        0,
+    );
+
     te.assert_output(
         &["--type", "empty"],
         "0_bytes.foo
@@ -959,82 +1566,401 @@ fn test_type_empty() {
     te.assert_output(&["--type", "empty", "--type", "directory"], "dir_empty");
 }
 
-/// File extension (--extension)
+/// ls -F style indicators (--classify)
 #[test]
-fn test_extension() {
+fn test_classify() {
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
+
+    te.assert_output(&["^one$", "--classify"], "one/");
+    te.assert_output(&["^a.foo$", "--classify"], "a.foo");
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+
+        fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .mode(0o777)
+            .open(te.test_root().join("executable-file.sh"))
+            .unwrap();
+
+        te.assert_output(&["^executable-file.sh$", "--classify"], "executable-file.sh*");
+    }
+}
+
+/// `--trailing-slash` appends '/' to directory results only, leaving files unmarked.
+#[test]
+fn test_trailing_slash() {
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
+
+    te.assert_output(&["^one$", "--trailing-slash"], "one/");
+    te.assert_output(&["^a.foo$", "--trailing-slash"], "a.foo");
+}
+
+/// Colorized output shows symlink targets as `link -> target`, using the orphan color for
+/// broken links.
+#[test]
+#[cfg(unix)]
+fn test_symlink_target_colorized() {
+    let mut te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
+    te.create_broken_symlink("broken_symlink")
+        .expect("Failed to create broken symlink.");
+
+    let output = te.assert_success_and_get_output(".", &["--color", "always", "^symlink$"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("-> "));
+    assert!(stdout.contains("two"));
+
+    let output =
+        te.assert_success_and_get_output(".", &["--color", "always", "^broken_symlink$"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("-> "));
+    assert!(stdout.contains("broken_symlink_target"));
+}
+
+/// The `NO_COLOR` environment variable disables color regardless of the TTY check, but
+/// `--color always` still takes precedence over it.
+#[test]
+fn test_no_color_env_var() {
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES).env("NO_COLOR", "1");
+
+    let output = te.assert_success_and_get_output(".", &["^one$"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("\x1b["));
+
+    let output = te.assert_success_and_get_output(".", &["--color", "always", "^one$"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("\x1b["));
+}
+
+/// The `FORCE_COLOR` environment variable enables color even on a non-interactive (redirected)
+/// stream, the positive-case complement to `NO_COLOR`.
+#[test]
+fn test_force_color_env_var() {
     let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
 
+    let output = te.assert_success_and_get_output(".", &["^one$"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("\x1b["));
+
+    let te = te.env("FORCE_COLOR", "1");
+    let output = te.assert_success_and_get_output(".", &["^one$"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("\x1b["));
+}
+
+/// A TOML config file at `$XDG_CONFIG_HOME/fd/config.toml` supplies default flags, with lower
+/// precedence than an explicit command-line flag.
+#[cfg(not(windows))] // TODO: make this work on Windows, where the config directory differs
+#[test]
+fn test_config_file_defaults() {
+    let config_home =
+        tempdir::TempDir::new("fd-tests-config").expect("failed to create temp config dir");
+    fs::create_dir_all(config_home.path().join("fd")).expect("failed to create fd config dir");
+    fs::write(
+        config_home.path().join("fd").join("config.toml"),
+        "hidden = true\n",
+    )
+    .expect("failed to write config file");
+
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES)
+        .env("XDG_CONFIG_HOME", config_home.path().to_str().unwrap());
+
+    // The config file's 'hidden = true' is applied.
     te.assert_output(
-        &["--extension", "foo"],
+        &["--max-depth", "1"],
+        ".fdignore
+        .git
+        .gitignore
+        .hidden.foo
+        a.foo
+        e1 e2
+        one
+        symlink",
+    );
+
+    // An explicit '--no-hidden' on the command line still overrides the config file default.
+    te.assert_output(
+        &["--max-depth", "1", "--no-hidden"],
         "a.foo
-        one/b.foo
-        one/two/c.foo
-        one/two/three/d.foo",
+        e1 e2
+        one
+        symlink",
     );
+}
+
+/// A config file default for a value-taking option (as opposed to a boolean flag) is still
+/// overridden by the same flag given explicitly on the command line, instead of clap rejecting
+/// the now-duplicated flag.
+#[cfg(not(windows))] // TODO: make this work on Windows, where the config directory differs
+#[test]
+fn test_config_file_defaults_value_flag_override() {
+    let config_home =
+        tempdir::TempDir::new("fd-tests-config").expect("failed to create temp config dir");
+    fs::create_dir_all(config_home.path().join("fd")).expect("failed to create fd config dir");
+    fs::write(
+        config_home.path().join("fd").join("config.toml"),
+        "max-depth = 1\n",
+    )
+    .expect("failed to write config file");
+
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES)
+        .env("XDG_CONFIG_HOME", config_home.path().to_str().unwrap());
 
     te.assert_output(
-        &["--extension", ".foo"],
+        &["--max-depth", "4", "foo"],
         "a.foo
         one/b.foo
         one/two/c.foo
-        one/two/three/d.foo",
+        one/two/C.Foo2
+        one/two/three/d.foo
+        one/two/three/directory_foo",
     );
+}
+
+/// The `FD_DEFAULT_OPTS` environment variable prepends default flags to the command line, and an
+/// explicit flag given on the command line still takes precedence over it.
+#[test]
+fn test_fd_default_opts_env_var() {
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES).env("FD_DEFAULT_OPTS", "--glob");
 
+    // The default from the environment is applied: '*.foo' is interpreted as a glob.
     te.assert_output(
-        &["--extension", ".foo", "--extension", "foo2"],
+        &["*.foo"],
         "a.foo
         one/b.foo
         one/two/c.foo
-        one/two/three/d.foo
-        one/two/C.Foo2",
+        one/two/three/d.foo",
     );
 
-    te.assert_output(&["--extension", ".foo", "a"], "a.foo");
-
-    te.assert_output(&["--extension", "foo2"], "one/two/C.Foo2");
+    // An explicit '--regex' on the command line still overrides the environment default.
+    te.assert_output(&["--regex", "Foo2$"], "one/two/C.Foo2");
+}
 
-    let te2 = TestEnv::new(&[], &["spam.bar.baz", "egg.bar.baz", "yolk.bar.baz.sig"]);
+/// An `FD_DEFAULT_OPTS` default for a value-taking option is still overridden by the same flag
+/// given explicitly on the command line, instead of clap rejecting the now-duplicated flag.
+#[test]
+fn test_fd_default_opts_env_var_value_flag_override() {
+    let te =
+        TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES).env("FD_DEFAULT_OPTS", "--color=never");
 
-    te2.assert_output(
-        &["--extension", ".bar.baz"],
-        "spam.bar.baz
-        egg.bar.baz",
-    );
+    let output = te.assert_success_and_get_output(".", &["--color=always", "^one$"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("\x1b["));
+}
 
-    te2.assert_output(&["--extension", "sig"], "yolk.bar.baz.sig");
+/// `--color always` forces colorized output even though the test harness always redirects
+/// stdout to a pipe (never an interactive terminal). `--color force` is an alias for it.
+#[test]
+fn test_color_always_forces_color_when_piped() {
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
 
-    te2.assert_output(&["--extension", "bar.baz.sig"], "yolk.bar.baz.sig");
+    let output = te.assert_success_and_get_output(".", &["--color", "always", "^one$"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("\x1b["));
 
-    let te3 = TestEnv::new(&[], &["latin1.e\u{301}xt", "smiley.☻"]);
+    let output = te.assert_success_and_get_output(".", &["--color", "force", "^one$"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("\x1b["));
+}
 
-    te3.assert_output(&["--extension", "☻"], "smiley.☻");
+/// If the process reading *fd*'s output closes the pipe early (e.g. piping into `head`), *fd*
+/// should exit quietly on the resulting broken pipe error instead of panicking.
+#[test]
+fn test_no_panic_on_broken_pipe() {
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
+    te.assert_no_panic_on_closed_stdout(&["--type", "f"]);
+}
 
-    te3.assert_output(&["--extension", ".e\u{301}xt"], "latin1.e\u{301}xt");
+/// `LS_COLORS` entries using 256-color (`38;5;n`) and truecolor (`38;2;r;g;b`) sequences are
+/// parsed and forwarded to the terminal unchanged.
+#[test]
+fn test_256_and_truecolor_ls_colors() {
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES)
+        .env("LS_COLORS", "*.foo=38;5;208:*.Foo2=38;2;255;128;0");
 
-    let te4 = TestEnv::new(&[], &[".hidden", "test.hidden"]);
+    let output = te.assert_success_and_get_output(".", &["--color", "always", "^a.foo$"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("38;5;208"));
 
-    te4.assert_output(&["--hidden", "--extension", ".hidden"], "test.hidden");
+    let output = te.assert_success_and_get_output(".", &["--color", "always", "^C.Foo2$"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("38;2;255;128;0"));
 }
 
-/// No file extension (test for the pattern provided in the --help text)
+/// When `LS_COLORS` is unset, `fd` falls back to a built-in theme instead of leaving
+/// colorized output unstyled.
 #[test]
-fn test_no_extension() {
-    let te = TestEnv::new(
-        DEFAULT_DIRS,
-        &["a.foo", "aa", "one/b.foo", "one/bb", "one/two/three/d"],
-    );
+fn test_default_colors_when_ls_colors_unset() {
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES).env_remove("LS_COLORS");
 
-    te.assert_output(
-        &["^[^.]+$"],
-        "aa
-        one
-        one/bb
-        one/two
-        one/two/three
-        one/two/three/d
-        one/two/three/directory_foo
-        symlink",
-    );
+    let output = te.assert_success_and_get_output(".", &["--color", "always", "^one$"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // Directories get a non-default style (a color escape sequence) out of the box.
+    assert!(stdout.contains("\x1b["));
+}
+
+/// When `LS_COLORS` is set but doesn't cover every indicator, `fd` fills in the gaps from its
+/// built-in theme instead of leaving the uncovered indicators unstyled.
+#[test]
+#[cfg(unix)]
+fn test_default_colors_fill_gaps_in_ls_colors() {
+    use std::os::unix::fs::OpenOptionsExt;
+
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES)
+        // No 'ex=' entry, so executables aren't covered by this (deliberately sparse) value.
+        .env("LS_COLORS", "*.foo=38;5;208");
+
+    fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .mode(0o777)
+        .open(te.test_root().join("executable-file.sh"))
+        .unwrap();
+
+    let output =
+        te.assert_success_and_get_output(".", &["--color", "always", "^executable-file.sh$"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // Falls back to fd's built-in executable style instead of printing a plain, unstyled path.
+    assert!(stdout.contains("\x1b["));
+}
+
+/// File extension (--extension)
+#[test]
+fn test_extension() {
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
+
+    te.assert_output(
+        &["--extension", "foo"],
+        "a.foo
+        one/b.foo
+        one/two/c.foo
+        one/two/three/d.foo",
+    );
+
+    te.assert_output(
+        &["--extension", ".foo"],
+        "a.foo
+        one/b.foo
+        one/two/c.foo
+        one/two/three/d.foo",
+    );
+
+    te.assert_output(
+        &["--extension", ".foo", "--extension", "foo2"],
+        "a.foo
+        one/b.foo
+        one/two/c.foo
+        one/two/three/d.foo
+        one/two/C.Foo2",
+    );
+
+    te.assert_output(&["--extension", ".foo", "a"], "a.foo");
+
+    te.assert_output(&["--extension", "foo2"], "one/two/C.Foo2");
+
+    let te2 = TestEnv::new(&[], &["spam.bar.baz", "egg.bar.baz", "yolk.bar.baz.sig"]);
+
+    te2.assert_output(
+        &["--extension", ".bar.baz"],
+        "spam.bar.baz
+        egg.bar.baz",
+    );
+
+    te2.assert_output(&["--extension", "sig"], "yolk.bar.baz.sig");
+
+    te2.assert_output(&["--extension", "bar.baz.sig"], "yolk.bar.baz.sig");
+
+    let te3 = TestEnv::new(&[], &["latin1.e\u{301}xt", "smiley.☻"]);
+
+    te3.assert_output(&["--extension", "☻"], "smiley.☻");
+
+    te3.assert_output(&["--extension", ".e\u{301}xt"], "latin1.e\u{301}xt");
+
+    // Compound extensions like `tar.gz`, and multiple `--extension` values OR-combined.
+    let te4 = TestEnv::new(&[], &["archive.tar.gz", "archive.zip", "notes.txt"]);
+
+    te4.assert_output(&["--extension", "tar.gz"], "archive.tar.gz");
+
+    te4.assert_output(
+        &["--extension", "tar.gz", "--extension", "zip"],
+        "archive.tar.gz
+        archive.zip",
+    );
+
+    // Extension matching is case-insensitive by default, but respects '--case-sensitive'.
+    let te5 = TestEnv::new(&[], &["a.jpg", "a.JPG"]);
+
+    te5.assert_output(
+        &["--extension", "JPG"],
+        "a.jpg
+        a.JPG",
+    );
+
+    te5.assert_output(&["--case-sensitive", "--extension", "JPG"], "a.JPG");
+
+    let te4 = TestEnv::new(&[], &[".hidden", "test.hidden"]);
+
+    te4.assert_output(&["--hidden", "--extension", ".hidden"], "test.hidden");
+}
+
+/// `--extension-mode any` matches a compound extension like 'tar.gz' by any of its dot-separated
+/// suffix components, not just the last one (the default, 'last', behavior).
+#[test]
+fn test_extension_mode() {
+    let te = TestEnv::new(&[], &["archive.tar.gz", "archive.zip", "notes.txt"]);
+
+    te.assert_no_matches(&["--extension", "tar"]);
+    te.assert_no_matches(&["--extension-mode", "last", "--extension", "tar"]);
+
+    te.assert_output(
+        &["--extension-mode", "any", "--extension", "tar"],
+        "archive.tar.gz",
+    );
+    te.assert_output(
+        &["--extension-mode", "any", "--extension", "gz"],
+        "archive.tar.gz",
+    );
+}
+
+#[test]
+fn test_extension_empty_matches_no_extension() {
+    let te = TestEnv::new(&[], &["Makefile", "a.rs", "archive.tar.gz"]);
+
+    // '--type f' excludes the symlink that every TestEnv includes by default, which would
+    // otherwise also count as an extensionless entry.
+    te.assert_output(&["--type", "f", "--extension", ""], "Makefile");
+
+    // Combined with a real extension, '--extension' matches either.
+    te.assert_output(
+        &["--type", "f", "--extension", "rs", "--extension", ""],
+        "Makefile
+        a.rs",
+    );
+
+    te.assert_output(&["--extension", "tar.gz"], "archive.tar.gz");
+}
+
+/// No file extension (test for the pattern provided in the --help text)
+#[test]
+fn test_no_extension() {
+    let te = TestEnv::new(
+        DEFAULT_DIRS,
+        &["a.foo", "aa", "one/b.foo", "one/bb", "one/two/three/d"],
+    );
+
+    te.assert_output(
+        &["^[^.]+$"],
+        "aa
+        one
+        one/bb
+        one/two
+        one/two/three
+        one/two/three/d
+        one/two/three/directory_foo
+        symlink",
+    );
 
     te.assert_output(
         &["^[^.]+$", "--type", "file"],
@@ -1086,6 +2012,36 @@ fn test_symlink_as_root() {
     );
 }
 
+/// `--resolve-root-symlink` rewrites results found under a symlinked search root to the root's
+/// resolved target, instead of the symlink path that was given on the command line.
+#[test]
+fn test_resolve_root_symlink() {
+    let (te, abs_path) = get_test_env_with_abs_path(DEFAULT_DIRS, DEFAULT_FILES);
+
+    // Without the flag, results keep the symlink path.
+    te.assert_output(
+        &["--follow", ".", "symlink"],
+        "symlink/c.foo
+        symlink/C.Foo2
+        symlink/three
+        symlink/three/d.foo
+        symlink/three/directory_foo",
+    );
+
+    // With the flag, results are displayed under the resolved target ('one/two') instead.
+    te.assert_output(
+        &["--follow", "--resolve-root-symlink", ".", "symlink"],
+        &format!(
+            "{abs_path}/one/two/c.foo
+            {abs_path}/one/two/C.Foo2
+            {abs_path}/one/two/three
+            {abs_path}/one/two/three/d.foo
+            {abs_path}/one/two/three/directory_foo",
+            abs_path = abs_path
+        ),
+    );
+}
+
 #[test]
 fn test_symlink_and_absolute_path() {
     let (te, abs_path) = get_test_env_with_abs_path(DEFAULT_DIRS, DEFAULT_FILES);
@@ -1162,6 +2118,73 @@ fn test_symlink_and_full_path_abs_path() {
         ),
     );
 }
+/// `--follow-and-match-target` matches a symlink against its resolved target path instead of
+/// its own name, and never matches a broken symlink.
+#[cfg(unix)]
+#[test]
+fn test_follow_and_match_target() {
+    let mut te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
+
+    // The target lives outside the search root, so only the symlink itself is a search result.
+    let target_dir =
+        tempdir::TempDir::new("fd-tests-target").expect("Failed to create external target dir.");
+    let target_path = target_dir.path().join("unique_target");
+    fs::create_dir(&target_path).expect("Failed to create target dir.");
+    std::os::unix::fs::symlink(&target_path, te.test_root().join("link_with_unrelated_name"))
+        .expect("Failed to create symlink.");
+
+    te.create_broken_symlink("broken_link")
+        .expect("Failed to create broken symlink.");
+
+    // The link's own name doesn't contain "unique_target", so a normal search finds nothing.
+    te.assert_no_matches(&["unique_target$"]);
+
+    // Matching against the resolved target finds it.
+    te.assert_output(
+        &["--follow-and-match-target", "unique_target$"],
+        "link_with_unrelated_name",
+    );
+
+    // The broken symlink's target name would match, but broken symlinks never match.
+    te.assert_no_matches(&["--follow-and-match-target", "broken_symlink_target"]);
+}
+
+/// Fd's default (and its explicit `--follow-roots` spelling) descends into a search root that
+/// is itself a symlink, but not into a symlink encountered elsewhere during the search;
+/// `--follow` descends into both, and `--no-follow-roots` descends into neither.
+#[test]
+fn test_follow_modes() {
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
+    te.create_symlink("one", "root_link")
+        .expect("Failed to create root symlink.");
+    te.create_symlink("one/two/three", "one/two/inner_link")
+        .expect("Failed to create nested symlink.");
+
+    let without_nested_symlink = "root_link/b.foo
+        root_link/two
+        root_link/two/c.foo
+        root_link/two/C.Foo2
+        root_link/two/three
+        root_link/two/three/d.foo
+        root_link/two/three/directory_foo
+        root_link/two/inner_link";
+
+    te.assert_output(&["", "root_link"], without_nested_symlink);
+    te.assert_output(&["--follow-roots", "", "root_link"], without_nested_symlink);
+
+    te.assert_output(
+        &["--follow", "", "root_link"],
+        &format!(
+            "{without_nested_symlink}
+            root_link/two/inner_link/d.foo
+            root_link/two/inner_link/directory_foo",
+            without_nested_symlink = without_nested_symlink
+        ),
+    );
+
+    te.assert_no_matches(&["--no-follow-roots", "", "root_link"]);
+}
+
 /// Exclude patterns (--exclude)
 #[test]
 fn test_excludes() {
@@ -1212,6 +2235,90 @@ fn test_excludes() {
     );
 }
 
+/// `--exclude` expands a single `{a,b}` alternation group before matching, since the
+/// gitignore-style globs it's matched against don't support brace expansion natively.
+#[test]
+fn test_exclude_brace_expansion() {
+    let te = TestEnv::new(&[], &["keep.rs", "drop.tmp", "drop.bak"]);
+
+    te.assert_output(
+        &["--exclude", "*.{tmp,bak}"],
+        "keep.rs
+        symlink",
+    );
+}
+
+/// `--exclude-regex` matches against the full relative path, rather than glob components.
+#[test]
+fn test_exclude_regex() {
+    let dirs = &["build/target/debug", "src/target"];
+    let files = &[
+        "build/target/debug/binary",
+        "src/target/generated.rs",
+        "src/main.rs",
+    ];
+    let te = TestEnv::new(dirs, files);
+
+    te.assert_output(
+        &["--exclude-regex", ".*/target/.*"],
+        "build
+        build/target
+        src
+        src/target
+        src/main.rs
+        symlink",
+    );
+
+    // Composes with the glob-style --exclude.
+    te.assert_output(
+        &[
+            "--exclude-regex",
+            ".*/target/.*",
+            "--exclude",
+            "build",
+            "--exclude",
+            "symlink",
+        ],
+        "src
+        src/target
+        src/main.rs",
+    );
+}
+
+/// Require multiple, independent patterns to all match (--and)
+#[test]
+fn test_and() {
+    let te = TestEnv::new(&[], &["abc", "ab", "bc", "ac", "abcd"]);
+
+    te.assert_output(
+        &["a", "--and", "b"],
+        "abc
+        ab
+        abcd",
+    );
+
+    // Three-way --and.
+    te.assert_output(&["a", "--and", "b", "--and", "d"], "abcd");
+}
+
+/// Exclude results whose name matches a complementary regex (--not)
+#[test]
+fn test_not() {
+    let te = TestEnv::new(&[], &["a.txt", "a.txt.bak", "b.txt.bak"]);
+
+    te.assert_output(
+        &["--type", "f", "."],
+        "a.txt
+        a.txt.bak
+        b.txt.bak",
+    );
+
+    te.assert_output(&["--type", "f", ".", "--not", r"\.bak$"], "a.txt");
+
+    // Multiple '--not' patterns exclude an entry if any of them match.
+    te.assert_no_matches(&["--type", "f", ".", "--not", r"\.bak$", "--not", "^a"]);
+}
+
 /// Shell script execution (--exec)
 #[test]
 fn test_exec() {
@@ -1241,6 +2348,21 @@ fn test_exec() {
             one/two/three/directory_foo",
         );
 
+        // The explicit '{}' placeholder receives the absolute path too, consistent with the
+        // printed output.
+        te.assert_output(
+            &["--absolute-path", "foo", "--exec", "echo", "{}"],
+            &format!(
+                "{abs_path}/a.foo
+                {abs_path}/one/b.foo
+                {abs_path}/one/two/C.Foo2
+                {abs_path}/one/two/c.foo
+                {abs_path}/one/two/three/d.foo
+                {abs_path}/one/two/three/directory_foo",
+                abs_path = &abs_path
+            ),
+        );
+
         te.assert_output(
             &["foo", "--exec", "echo", "{.}"],
             "a
@@ -1282,43 +2404,193 @@ fn test_exec() {
         );
 
         te.assert_output(&["e1", "--exec", "printf", "%s.%s\n"], "e1 e2.");
+
+        // --exec is dispatched onto a thread pool sized by --threads; this should
+        // work the same whether that pool has one worker or several.
+        te.assert_output(
+            &["foo", "--threads=1", "--exec", "echo", "{/}"],
+            "a.foo
+            b.foo
+            C.Foo2
+            c.foo
+            d.foo
+            directory_foo",
+        );
     }
 }
 
+/// A command that runs longer than --exec-timeout is killed, and counts as a failure.
+#[cfg(not(windows))] // TODO: make this work on Windows
 #[test]
-fn test_exec_batch() {
-    let (te, abs_path) = get_test_env_with_abs_path(DEFAULT_DIRS, DEFAULT_FILES);
-    let te = te.normalize_line(true);
-
-    // TODO Test for windows
-    if !cfg!(windows) {
-        te.assert_output(
-            &["--absolute-path", "foo", "--exec-batch", "echo"],
-            &format!(
-                "{abs_path}/a.foo {abs_path}/one/b.foo {abs_path}/one/two/C.Foo2 {abs_path}/one/two/c.foo {abs_path}/one/two/three/d.foo {abs_path}/one/two/three/directory_foo",
-                abs_path = &abs_path
-            ),
-        );
+fn test_exec_timeout() {
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
 
-        te.assert_output(
-            &["foo", "--exec-batch", "echo", "{}"],
-            "a.foo one/b.foo one/two/C.Foo2 one/two/c.foo one/two/three/d.foo one/two/three/directory_foo",
-        );
+    let start = std::time::Instant::now();
+    te.assert_failure(&[
+        "a.foo",
+        "--exec-timeout",
+        "100ms",
+        "--exec",
+        "sh",
+        "-c",
+        "sleep 5",
+    ]);
+    assert!(
+        start.elapsed() < std::time::Duration::from_secs(2),
+        "the timed-out command should have been killed well before it could sleep for 5s"
+    );
 
-        te.assert_output(
-            &["foo", "--exec-batch", "echo", "{/}"],
-            "a.foo b.foo C.Foo2 c.foo d.foo directory_foo",
-        );
+    te.assert_failure_with_error(
+        &["a.foo", "--exec-timeout", "100ms"],
+        "[fd error]: '--exec-timeout' can only be used together with '--exec' or '--exec-batch'.",
+    );
+}
 
-        te.assert_output(
-            &["no_match", "--exec-batch", "echo", "Matched: ", "{/}"],
-            "",
-        );
+/// Run the command in --exec with its working directory set to the match's parent (--exec-cwd)
+#[cfg(not(windows))] // TODO: make this work on Windows
+#[test]
+fn test_exec_cwd() {
+    let (te, abs_path) = get_test_env_with_abs_path(DEFAULT_DIRS, DEFAULT_FILES);
 
-        te.assert_failure_with_error(
-            &["foo", "--exec-batch", "echo", "{}", "{}"],
-            "[fd error]: Only one placeholder allowed for batch commands",
-        );
+    te.assert_output(
+        &["--absolute-path", "a.foo", "--exec-cwd", "--exec", "sh", "-c", "pwd"],
+        &abs_path,
+    );
+
+    te.assert_output(
+        &[
+            "--absolute-path",
+            "b.foo",
+            "--exec-cwd",
+            "--exec",
+            "sh",
+            "-c",
+            "pwd",
+        ],
+        &format!("{abs_path}/one", abs_path = &abs_path),
+    );
+
+    te.assert_failure_with_error(
+        &["a.foo", "--exec-cwd"],
+        "[fd error]: '--exec-cwd' can only be used together with '--exec'.",
+    );
+
+    te.assert_failure_with_error(
+        &["a.foo", "--exec-cwd", "--exec-batch", "echo"],
+        "[fd error]: '--exec-cwd' can only be used together with '--exec'.",
+    );
+}
+
+/// Dry-run mode for --exec and --exec-batch (--exec-dry-run)
+#[test]
+fn test_exec_dry_run() {
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
+
+    te.assert_output(
+        &[
+            "a.foo",
+            "--exec-dry-run",
+            "--exec",
+            "touch",
+            "dry-run-marker",
+            "{}",
+        ],
+        "touch dry-run-marker a.foo",
+    );
+    assert!(!te.test_root().join("dry-run-marker").exists());
+
+    let te = te.normalize_line(true);
+    te.assert_output(
+        &[
+            "foo",
+            "--exec-dry-run",
+            "--exec-batch",
+            "touch",
+            "dry-run-marker",
+        ],
+        "touch dry-run-marker a.foo one/b.foo one/two/C.Foo2 one/two/c.foo one/two/three/d.foo one/two/three/directory_foo",
+    );
+    assert!(!te.test_root().join("dry-run-marker").exists());
+
+    te.assert_failure_with_error(
+        &["a.foo", "--exec-dry-run"],
+        "[fd error]: '--exec-dry-run' can only be used together with '--exec' or '--exec-batch'.",
+    );
+}
+
+/// The '{#}' placeholder is substituted with a 1-based index. With '--threads=1', the commands
+/// run sequentially, so the indices are handed out in a deterministic, increasing order.
+#[test]
+fn test_exec_index_placeholder_sequential_with_single_thread() {
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
+
+    let output = te.assert_success_and_get_output(
+        ".",
+        &[
+            "foo",
+            "--threads=1",
+            "--exec-dry-run",
+            "--exec",
+            "echo",
+            "{#}",
+        ],
+    );
+    let stdout = String::from_utf8(output.stdout).expect("valid utf-8");
+
+    let indices: Vec<usize> = stdout
+        .lines()
+        .map(|line| {
+            // '{#}' doesn't refer to the path, so fd appends a trailing '{}' automatically;
+            // the index is therefore the first of the two arguments after 'echo'.
+            line.trim_start_matches("echo ")
+                .split_whitespace()
+                .next()
+                .expect("line has at least one argument")
+                .parse()
+                .expect("first argument is a numeric index")
+        })
+        .collect();
+
+    let mut sorted_indices = indices.clone();
+    sorted_indices.sort_unstable();
+    assert_eq!(indices, sorted_indices);
+    assert_eq!(indices, (1..=indices.len()).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_exec_batch() {
+    let (te, abs_path) = get_test_env_with_abs_path(DEFAULT_DIRS, DEFAULT_FILES);
+    let te = te.normalize_line(true);
+
+    // TODO Test for windows
+    if !cfg!(windows) {
+        te.assert_output(
+            &["--absolute-path", "foo", "--exec-batch", "echo"],
+            &format!(
+                "{abs_path}/a.foo {abs_path}/one/b.foo {abs_path}/one/two/C.Foo2 {abs_path}/one/two/c.foo {abs_path}/one/two/three/d.foo {abs_path}/one/two/three/directory_foo",
+                abs_path = &abs_path
+            ),
+        );
+
+        te.assert_output(
+            &["foo", "--exec-batch", "echo", "{}"],
+            "a.foo one/b.foo one/two/C.Foo2 one/two/c.foo one/two/three/d.foo one/two/three/directory_foo",
+        );
+
+        te.assert_output(
+            &["foo", "--exec-batch", "echo", "{/}"],
+            "a.foo b.foo C.Foo2 c.foo d.foo directory_foo",
+        );
+
+        te.assert_output(
+            &["no_match", "--exec-batch", "echo", "Matched: ", "{/}"],
+            "",
+        );
+
+        te.assert_failure_with_error(
+            &["foo", "--exec-batch", "echo", "{}", "{}"],
+            "[fd error]: Only one placeholder allowed for batch commands",
+        );
 
         te.assert_failure_with_error(
             &["foo", "--exec-batch", "echo", "{/}", ";", "-x", "echo"],
@@ -1334,6 +2606,12 @@ fn test_exec_batch() {
             &["foo", "--exec-batch", "echo {}"],
             "[fd error]: First argument of exec-batch is expected to be a fixed executable",
         );
+
+        // The short form '-X' is equivalent to '--exec-batch'.
+        te.assert_output(
+            &["foo", "-X", "echo"],
+            "a.foo one/b.foo one/two/C.Foo2 one/two/c.foo one/two/three/d.foo one/two/three/directory_foo",
+        );
     }
 }
 
@@ -1434,7 +2712,7 @@ fn test_fixed_strings() {
     te.assert_output(&["--fixed-strings", "a.foo"], "test1/a.foo");
 
     // Regex search, parens are treated as group
-    te.assert_output(&["download (1)"], "");
+    te.assert_no_matches(&["download (1)"]);
 
     // Literal search, parens are treated as characters
     te.assert_output(
@@ -1443,7 +2721,17 @@ fn test_fixed_strings() {
     );
 
     // Combine with --case-sensitive
-    te.assert_output(&["--fixed-strings", "--case-sensitive", "download (1)"], "");
+    te.assert_no_matches(&["--fixed-strings", "--case-sensitive", "download (1)"]);
+}
+
+/// Fixed-string search treats the pattern as a literal substring, not a regular expression
+#[test]
+fn test_fixed_strings_literal_dot() {
+    let dirs = &[];
+    let files = &["a.b", "axb"];
+    let te = TestEnv::new(dirs, files);
+
+    te.assert_output(&["--fixed-strings", "a.b"], "a.b");
 }
 
 /// Filenames with invalid UTF-8 sequences
@@ -1468,7 +2756,7 @@ fn test_invalid_utf8() {
     te.assert_output(&["invalid", "test1/"], "test1/test_�invalid.txt");
 
     // Should not be found under a different extension
-    te.assert_output(&["-e", "zip", "", "test1/"], "");
+    te.assert_no_matches(&["-e", "zip", "", "test1/"]);
 }
 
 /// Filtering for file size (--size)
@@ -1530,7 +2818,7 @@ fn test_size() {
     te.assert_output(&["", "--size", "+12B", "--size", "-30B"], "30_bytes.foo");
 
     // Files with size between 31 and 100 bytes.
-    te.assert_output(&["", "--size", "+31B", "--size", "-100B"], "");
+    te.assert_no_matches(&["", "--size", "+31B", "--size", "-100B"]);
 
     // Files with size between 3 kibibytes and 5 kibibytes.
     te.assert_output(&["", "--size", "+3ki", "--size", "-5ki"], "4_kibibytes.foo");
@@ -1550,6 +2838,57 @@ fn test_size() {
     te.assert_output(&["", "--size", "4ki"], "4_kibibytes.foo");
 }
 
+/// `--size` is type-aware: by default it only applies to regular files, leaving directories
+/// (which are tiny on disk, regardless of their contents) unaffected. With '--type d', it
+/// applies to directories' own size instead, so oversized directories can be found too.
+#[test]
+fn test_size_type_aware() {
+    let te = TestEnv::new(&["some_dir"], &[]);
+    create_file_with_size(te.test_root().join("some_dir").join("small.foo"), 11);
+
+    // The tiny file is dropped by the size filter, but the directory passes through untouched.
+    te.assert_output(&["", "--size", "+1M"], "some_dir");
+
+    // With '--type d', the filter applies to the directory's own (tiny) size, dropping it too.
+    te.assert_no_matches(&["", "--size", "+1M", "--type", "d"]);
+}
+
+/// Sorting results with '--sort'
+#[test]
+fn test_sort() {
+    let te = TestEnv::new(&[], &[]);
+
+    create_file_with_size(te.test_root().join("b_11_bytes.foo"), 11);
+    create_file_with_size(te.test_root().join("a_30_bytes.foo"), 30);
+    create_file_with_size(te.test_root().join("c_0_bytes.foo"), 0);
+
+    let by_name = |args: &[&str]| {
+        let output = te.assert_success_and_get_output(".", args);
+        String::from_utf8(output.stdout).expect("valid utf-8 output")
+    };
+
+    // Ascending order by name is stable regardless of the order files were created in.
+    assert_eq!(
+        "a_30_bytes.foo\nb_11_bytes.foo\nc_0_bytes.foo\n",
+        by_name(&["--extension", "foo", "--sort", "name"])
+    );
+
+    // Ascending order by size.
+    assert_eq!(
+        "c_0_bytes.foo\nb_11_bytes.foo\na_30_bytes.foo\n",
+        by_name(&["--extension", "foo", "--sort", "size"])
+    );
+
+    // Descending order by size.
+    assert_eq!(
+        "a_30_bytes.foo\nb_11_bytes.foo\nc_0_bytes.foo\n",
+        by_name(&["--extension", "foo", "--sort", "size", "--sort-reverse"])
+    );
+
+    // --sort-reverse requires --sort
+    te.assert_failure(&["", "--sort-reverse"]);
+}
+
 #[cfg(test)]
 fn create_file_with_modified<P: AsRef<Path>>(path: P, duration_in_secs: u64) {
     let st = SystemTime::now() - Duration::from_secs(duration_in_secs);
@@ -1613,6 +2952,48 @@ fn test_modified_asolute() {
     );
 }
 
+/// `--changed-within` combined with `--type d` matches a directory's own mtime, not the mtime
+/// of anything recursively contained within it.
+#[test]
+fn test_modified_directory() {
+    let te = TestEnv::new(&["old_dir", "new_dir"], &[]);
+
+    let ft_old =
+        filetime::FileTime::from_system_time(SystemTime::now() - Duration::from_secs(24 * 60 * 60));
+    filetime::set_file_times(te.test_root().join("old_dir"), ft_old, ft_old).unwrap();
+
+    let ft_new = filetime::FileTime::from_system_time(SystemTime::now());
+    filetime::set_file_times(te.test_root().join("new_dir"), ft_new, ft_new).unwrap();
+
+    te.assert_output(&["--type", "d", "--changed-within", "15min"], "new_dir");
+}
+
+/// `--newer-than`/`--older-than` compare against the modification time of a reference file,
+/// instead of a fixed date or duration.
+#[test]
+fn test_newer_than_older_than() {
+    let te = TestEnv::new(&[], &[]);
+    create_file_with_modified(te.test_root().join("old"), 2 * 60 * 60);
+    create_file_with_modified(te.test_root().join("reference"), 60 * 60);
+    create_file_with_modified(te.test_root().join("new"), 0);
+
+    te.assert_output(
+        &["", "--newer-than", "reference"],
+        "new
+        reference",
+    );
+    te.assert_output(
+        &["", "--older-than", "reference"],
+        "old
+        reference",
+    );
+
+    te.assert_error(
+        &["", "--newer-than", "does-not-exist"],
+        "[fd error]: 'does-not-exist' is not a valid path",
+    );
+}
+
 #[test]
 fn test_custom_path_separator() {
     let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
@@ -1676,6 +3057,91 @@ fn test_base_directory() {
     );
 }
 
+/// `--owner` filters by the numeric uid/gid of the process's own files.
+#[test]
+#[cfg(unix)]
+fn test_owner() {
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
+
+    let uid = unsafe { libc::geteuid() };
+    let gid = unsafe { libc::getegid() };
+
+    // Every test file is owned by the current user/group, so a matching filter keeps them all...
+    let output = te.assert_success_and_get_output(".", &["--owner", &format!("{}:{}", uid, gid)]);
+    assert!(!String::from_utf8_lossy(&output.stdout).is_empty());
+
+    // ...while negating it excludes them all.
+    te.assert_no_matches(&["--owner", &format!("!{}", uid)]);
+}
+
+/// `--perm` filters by permission bits, using `find`-style exact/all/any semantics.
+#[test]
+#[cfg(unix)]
+fn test_perm() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
+    fs::set_permissions(
+        te.test_root().join("a.foo"),
+        fs::Permissions::from_mode(0o644),
+    )
+    .unwrap();
+    fs::set_permissions(
+        te.test_root().join("one/b.foo"),
+        fs::Permissions::from_mode(0o600),
+    )
+    .unwrap();
+
+    // Exact match.
+    te.assert_output(&["--perm", "644", "^a.foo$"], "a.foo");
+
+    // '-' requires all of the given bits to be set; both files are owner-readable.
+    te.assert_output(
+        &["--perm", "-600", "^(a|b)\\.foo$"],
+        "a.foo
+        one/b.foo",
+    );
+
+    // '/' requires any of the given bits to be set; only 'a.foo' is group/other readable.
+    te.assert_output(&["--perm", "/o+r", "^(a|b)\\.foo$"], "a.foo");
+}
+
+/// `--same-file-as` filters by device/inode, matching hardlinks of the given reference file.
+#[test]
+#[cfg(unix)]
+fn test_same_file_as() {
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
+
+    fs::hard_link(
+        te.test_root().join("a.foo"),
+        te.test_root().join("a.foo.hardlink"),
+    )
+    .unwrap();
+
+    te.assert_output(
+        &["--same-file-as", "a.foo"],
+        "a.foo
+        a.foo.hardlink",
+    );
+
+    // A reference path that doesn't exist is an error.
+    te.assert_error(
+        &["--same-file-as", "does-not-exist"],
+        "[fd error]: 'does-not-exist' is not a valid path",
+    );
+}
+
+/// An invalid `--base-directory` is reported as an error instead of being silently ignored.
+#[test]
+fn test_base_directory_must_be_a_directory() {
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
+
+    te.assert_error(
+        &["--base-directory", "does-not-exist", "foo"],
+        "[fd error]: The '--base-directory' path 'does-not-exist' is not a directory.",
+    );
+}
+
 #[test]
 fn test_max_results() {
     let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
@@ -1704,6 +3170,17 @@ fn test_max_results() {
     };
     assert_just_one_result_with_option("--max-results=1");
     assert_just_one_result_with_option("-1");
+    assert_just_one_result_with_option("--first");
+
+    // With more than two matching entries, --max-results=N must emit exactly N lines, even
+    // though the parallel walk may have produced (and discarded) a few more internally.
+    let dirs = &[
+        "dir0", "dir1", "dir2", "dir3", "dir4", "dir5", "dir6", "dir7", "dir8", "dir9",
+    ];
+    let te = TestEnv::new(dirs, &[]);
+    let output = te.assert_success_and_get_output(".", &["--max-results=3", "^dir"]);
+    let stdout = String::from_utf8(output.stdout).expect("valid utf-8 output");
+    assert_eq!(3, stdout.lines().count());
 }
 
 /// Filenames with non-utf8 paths are passed to the executed program unchanged
@@ -1758,6 +3235,40 @@ fn test_list_details() {
 
     // Make sure we can execute 'fd --list-details' without any errors.
     te.assert_success_and_get_output(".", &["--list-details"]);
+
+    // The size column should reflect the fixture file's actual length.
+    #[cfg(unix)]
+    {
+        create_file_with_size(te.test_root().join("12_bytes.foo"), 12);
+
+        let output = te.assert_success_and_get_output(".", &["--list-details", "^12_bytes.foo$"]);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let line = stdout.trim();
+
+        assert!(
+            line.split_whitespace().any(|field| field == "12"),
+            "expected a size column of 12 in: {}",
+            line
+        );
+    }
+}
+
+/// Make sure that '--format json' produces one valid JSON object per line.
+#[test]
+fn test_format_json() {
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
+
+    let output = te.assert_success_and_get_output(".", &["a.foo", "--format", "json"]);
+    let stdout = String::from_utf8(output.stdout).expect("valid utf-8 output");
+
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(1, lines.len());
+
+    let entry: serde_json::Value = serde_json::from_str(lines[0]).expect("valid JSON");
+    assert_eq!("a.foo", entry["path"]);
+    assert_eq!("file", entry["file_type"]);
+    assert_eq!(false, entry["path_lossy"]);
+    assert!(entry["modified"].is_string());
 }
 
 /// Make sure that fd fails if numeric arguments can not be parsed
@@ -1767,7 +3278,7 @@ fn test_number_parsing_errors() {
 
     te.assert_failure(&["--threads=a"]);
     te.assert_failure(&["-j", ""]);
-    te.assert_failure(&["--threads=0"]);
+    te.assert_failure(&["--threads=-1"]);
 
     te.assert_failure(&["--min-depth=a"]);
     te.assert_failure(&["--max-depth=a"]);
@@ -1779,6 +3290,289 @@ fn test_number_parsing_errors() {
     te.assert_failure(&["--max-results=a"]);
 }
 
+/// An invalid `--color` value is rejected instead of silently falling back to 'auto'.
+#[test]
+fn test_invalid_color_value() {
+    let te = TestEnv::new(&[], &[]);
+
+    te.assert_failure(&["--color", "alway"]);
+    te.assert_failure(&["--color", ""]);
+}
+
+/// Shell-safe quoting of results (--quote)
+#[test]
+fn test_quote() {
+    let files = &["plain.txt", "has space.txt", "it's quoted.txt"];
+    let te = TestEnv::new(&[], files);
+
+    te.assert_output(
+        &["--quote", "--extension", "txt"],
+        "'has space.txt'
+        'it'\\''s quoted.txt'
+        'plain.txt'",
+    );
+}
+
+/// `--quote` is mutually exclusive with `--print0`.
+#[test]
+fn test_quote_conflicts_with_print0() {
+    let te = TestEnv::new(&[], &[]);
+
+    te.assert_failure(&["--quote", "--print0"]);
+}
+
+/// A directory that can't be read is reported on stderr but does not stop the search from
+/// returning its readable siblings. Like `find`/`rg`, a filesystem error encountered along the
+/// way still causes a non-zero (`GeneralError`) exit code, even though the search itself found
+/// matches.
+#[test]
+#[cfg(unix)]
+fn test_permission_denied() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let te = TestEnv::new(&["unreadable", "readable"], &["readable/a.foo"]);
+    let unreadable = te.test_root().join("unreadable");
+    fs::set_permissions(&unreadable, fs::Permissions::from_mode(0o000)).unwrap();
+
+    // When running as root, permission bits don't actually block access, so there is nothing
+    // to test.
+    if fs::read_dir(&unreadable).is_ok() {
+        return;
+    }
+
+    // By default, only a one-line summary of the error is shown on stderr...
+    let output = te.assert_failure_and_get_output(&["a.foo"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("error(s) occurred"));
+    assert!(stderr.contains("--show-errors"));
+
+    // ...but the readable sibling is still found.
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("readable/a.foo"));
+
+    // '--show-errors' surfaces the underlying filesystem error instead of the summary.
+    let output = te.assert_failure_and_get_output(&["--show-errors", "a.foo"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("[fd error]"));
+    assert!(!stderr.contains("error(s) occurred"));
+
+    // '--quiet' suppresses all diagnostics, even together with '--show-errors', but the exit
+    // code still reflects the error.
+    let output = te.assert_failure_and_get_output(&["--quiet", "--show-errors", "a.foo"]);
+    assert!(output.stderr.is_empty());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("readable/a.foo"));
+
+    fs::set_permissions(te.test_root().join("unreadable"), fs::Permissions::from_mode(0o755))
+        .unwrap();
+}
+
+/// `--quiet` suppresses the "not a directory" warning for an invalid search path, but the
+/// exit status still reflects that no valid search path was found.
+#[test]
+fn test_quiet_suppresses_invalid_path_warning() {
+    let te = TestEnv::new(&[], &[]);
+
+    te.assert_error(
+        &["foo", "does-not-exist"],
+        "[fd error]: Search path 'does-not-exist' is not a directory.
+        [fd error]: No valid search paths given.",
+    );
+
+    let output = te.assert_failure_and_get_output(&["--quiet", "foo", "does-not-exist"]);
+    assert!(output.stderr.is_empty());
+}
+
+/// `--stats` prints a summary of the search to stderr without polluting stdout.
+#[test]
+fn test_stats() {
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
+
+    let output = te.assert_success_and_get_output(".", &["--stats", "foo"]);
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("matches found"));
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("6 matches found"));
+    assert!(stderr.contains("directories visited"));
+}
+
+/// `--count` prints only the number of matches, and still respects filters like `--stats`
+/// printing its summary to stderr.
+#[test]
+fn test_count() {
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
+
+    te.assert_output(&["--count", "foo"], "6");
+
+    let output = te.assert_success_and_get_output(".", &["--count", "--stats", "foo"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.trim_end(), "6");
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("6 matches found"));
+}
+
+/// `--progress` is silently disabled when stderr isn't a tty (as is always the case when output
+/// is piped, e.g. in these tests), so it must not corrupt stdout or leak stray output.
+#[test]
+fn test_progress_disabled_without_tty() {
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
+
+    te.assert_output(
+        &["--progress", "foo"],
+        "a.foo
+        one/b.foo
+        one/two/c.foo
+        one/two/C.Foo2
+        one/two/three/d.foo
+        one/two/three/directory_foo",
+    );
+
+    let output = te.assert_success_and_get_output(".", &["--progress", "foo"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.is_empty());
+}
+
+/// `--hyperlink` is silently disabled when stdout isn't a tty (as is always the case when
+/// output is piped, e.g. in these tests), so it must not leak escape sequences into the output.
+#[test]
+fn test_hyperlink_disabled_without_tty() {
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
+
+    let output = te.assert_success_and_get_output(".", &["--hyperlink", "a.foo"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout, "a.foo\n");
+}
+
+/// `--strip-cwd-prefix` removes the leading `./` from results found under an explicit `.` root.
+#[test]
+fn test_strip_cwd_prefix() {
+    let te = TestEnv::new(DEFAULT_DIRS, &["a.foo"]);
+
+    // No leading './' is ever produced for an explicitly given, non-'.' root.
+    te.assert_output(&["foo", "one"], "one/two/three/directory_foo");
+
+    // A '.' root never shows a leading './', with or without the flag.
+    te.assert_output(&["a.foo", "."], "a.foo");
+    te.assert_output(&["--strip-cwd-prefix", "a.foo", "."], "a.foo");
+}
+
+/// `--relative-to <dir>` shows each result relative to the given directory instead of the
+/// current working directory, falling back to an absolute path for results outside of it.
+#[test]
+fn test_relative_to() {
+    let (te, root) = get_test_env_with_abs_path(DEFAULT_DIRS, DEFAULT_FILES);
+
+    te.assert_output(
+        &["--relative-to", "one/two", "--type", "f", "foo"],
+        &format!(
+            "{root}/a.foo
+            {root}/one/b.foo
+            c.foo
+            C.Foo2
+            three/d.foo",
+            root = root
+        ),
+    );
+
+    te.assert_output(
+        &["--relative-to", "one/two/three", "--type", "f", "foo"],
+        &format!(
+            "{root}/a.foo
+            {root}/one/b.foo
+            {root}/one/two/c.foo
+            {root}/one/two/C.Foo2
+            d.foo",
+            root = root
+        ),
+    );
+}
+
+/// `--relative-to` prints distinct paths reached through a symlink as distinct, rather than
+/// collapsing them to the same canonical path.
+#[test]
+fn test_relative_to_does_not_collapse_symlinked_paths() {
+    let te = TestEnv::new(&["a", "b"], &["b/file.txt"]);
+    te.create_symlink("b", "a/linked")
+        .expect("Failed to create symlink.");
+
+    te.assert_output(
+        &["--follow", "--relative-to", ".", "file.txt"],
+        "a/linked/file.txt
+        b/file.txt",
+    );
+}
+
+/// `--max-buffer-time=0` disables buffering, streaming results immediately.
+#[test]
+fn test_max_buffer_time_zero_disables_buffering() {
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
+
+    te.assert_output(
+        &["--max-buffer-time", "0", "foo"],
+        "a.foo
+        one/b.foo
+        one/two/c.foo
+        one/two/C.Foo2
+        one/two/three/d.foo
+        one/two/three/directory_foo",
+    );
+}
+
+/// `--no-buffer` bypasses the internal buffering entirely, flushing after every result; this
+/// must not change what's found, even though `fd`'s output here is captured through a pipe.
+#[test]
+fn test_no_buffer() {
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
+
+    te.assert_output(
+        &["--no-buffer", "foo"],
+        "a.foo
+        one/b.foo
+        one/two/c.foo
+        one/two/C.Foo2
+        one/two/three/d.foo
+        one/two/three/directory_foo",
+    );
+}
+
+/// `--threads=0` means "automatically detect the number of CPUs", same as the default.
+#[test]
+fn test_threads_zero_means_auto() {
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
+
+    te.assert_output(
+        &["--threads=0", "foo"],
+        "a.foo
+        one/b.foo
+        one/two/c.foo
+        one/two/C.Foo2
+        one/two/three/d.foo
+        one/two/three/directory_foo",
+    );
+}
+
+/// `--threads-ratio` scales the thread count without affecting what's found, and conflicts with
+/// the explicit `--threads` option.
+#[test]
+fn test_threads_ratio() {
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
+
+    te.assert_output(
+        &["--threads-ratio=0.5", "foo"],
+        "a.foo
+        one/b.foo
+        one/two/c.foo
+        one/two/C.Foo2
+        one/two/three/d.foo
+        one/two/three/directory_foo",
+    );
+
+    te.assert_failure(&["--threads", "2", "--threads-ratio", "0.5", "foo"]);
+}
+
 /// Print error if search pattern starts with a dot and --hidden is not set
 /// (Unix only, hidden files on Windows work differently)
 #[test]
@@ -1791,5 +3585,30 @@ fn test_error_if_hidden_not_set_and_pattern_starts_with_dot() {
 
     te.assert_output(&["--hidden", "^\\.gitignore"], ".gitignore");
     te.assert_output(&["--hidden", "--glob", ".gitignore"], ".gitignore");
-    te.assert_output(&[".gitignore"], "");
+    te.assert_no_matches(&[".gitignore"]);
+}
+
+/// `fd` exits with a dedicated non-zero status when the search found no matches, so that it
+/// can be used in shell conditionals like `if fd foo; then ...`.
+#[test]
+fn test_exit_code_based_on_matches() {
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
+
+    te.assert_output(&["a.foo"], "a.foo");
+    te.assert_no_matches(&["nonexistent-pattern-xyz"]);
+}
+
+/// If any of the commands spawned by --exec exits with a non-zero status, fd propagates that
+/// failure instead of reporting success merely because the search itself completed.
+#[test]
+fn test_exit_code_based_on_exec() {
+    let te = TestEnv::new(DEFAULT_DIRS, DEFAULT_FILES);
+
+    // Every spawned 'true' succeeds, so fd exits successfully too.
+    let output = te.assert_success_and_get_output(".", &["foo", "--exec", "true"]);
+    assert!(output.status.success());
+
+    // Every spawned 'false' fails, so fd's exit code reflects that.
+    let output = te.assert_failure_and_get_output(&["foo", "--exec", "false"]);
+    assert_eq!(output.status.code(), Some(2));
 }